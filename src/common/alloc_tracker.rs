@@ -0,0 +1,89 @@
+//! Instrumented global allocator for precise in-process heap measurements
+//!
+//! [`crate::common::test_utils::MemoryTracker`]'s memory deltas used to be
+//! a placeholder (`size_of::<System>()`, which never changes), so they
+//! couldn't actually catch a regression in something like
+//! [`crate::storage::cache::BufferPool`]'s footprint. [`TrackingAllocator`]
+//! wraps [`System`] and keeps a pair of process-wide atomic counters —
+//! bytes currently live and the high-water mark ever reached — that
+//! [`current_bytes`] and [`peak_bytes`] expose for `MemoryTracker` to read.
+//!
+//! This is behind the `track-allocations` feature because installing a
+//! `#[global_allocator]` is a whole-binary decision: it's only meant to be
+//! turned on for test/CI runs that want enforceable memory assertions, not
+//! for production builds paying the bookkeeping overhead on every
+//! allocation.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+fn record_alloc(size: usize) {
+    let now = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(now, Ordering::Relaxed);
+}
+
+fn record_dealloc(size: usize) {
+    ALLOCATED.fetch_sub(size, Ordering::Relaxed);
+}
+
+/// `#[global_allocator]` wrapper around [`System`] that tallies bytes
+/// currently allocated and the peak ever reached. Install it with:
+///
+/// ```ignore
+/// #[global_allocator]
+/// static GLOBAL_ALLOCATOR: TrackingAllocator = TrackingAllocator;
+/// ```
+pub struct TrackingAllocator;
+
+// SAFETY: every method delegates directly to `System`, which is itself a
+// valid `GlobalAlloc`; the atomic counters are just bookkeeping around
+// calls that already satisfy `GlobalAlloc`'s safety contract.
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        record_dealloc(layout.size());
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Bytes currently live on the heap, as tracked by [`TrackingAllocator`].
+///
+/// Reads as `0` if `TrackingAllocator` was never installed as the
+/// `#[global_allocator]`.
+#[must_use]
+pub fn current_bytes() -> usize {
+    ALLOCATED.load(Ordering::Relaxed)
+}
+
+/// Highest value [`current_bytes`] has ever reached since process start.
+#[must_use]
+pub fn peak_bytes() -> usize {
+    PEAK.load(Ordering::Relaxed)
+}
@@ -22,6 +22,19 @@ pub enum Error {
     TransactionConflict(String),
     /// Internal database error
     Internal(String),
+    /// A page's stored checksum didn't match the checksum computed from
+    /// its current bytes
+    ChecksumMismatch {
+        /// ID of the page that failed verification
+        page_id: u64,
+        /// Checksum recorded in the page header
+        expected: u32,
+        /// Checksum computed from the page's current bytes
+        found: u32,
+    },
+    /// A page header's `page_type` byte didn't match any known
+    /// [`crate::storage::page_type::PageType`] variant
+    InvalidPageType(u8),
 }
 
 impl fmt::Display for Error {
@@ -34,6 +47,15 @@ impl fmt::Display for Error {
             Error::OutOfMemory => write!(f, "Out of memory"),
             Error::TransactionConflict(msg) => write!(f, "Transaction conflict: {msg}"),
             Error::Internal(msg) => write!(f, "Internal error: {msg}"),
+            Error::ChecksumMismatch {
+                page_id,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Checksum mismatch for page {page_id}: expected {expected:#010x}, found {found:#010x}"
+            ),
+            Error::InvalidPageType(value) => write!(f, "Invalid page type: {value:#04x}"),
         }
     }
 }
@@ -83,6 +105,15 @@ impl Error {
         Error::Internal(msg.into())
     }
 
+    /// Create a checksum mismatch error
+    pub fn checksum_mismatch(page_id: u64, expected: u32, found: u32) -> Self {
+        Error::ChecksumMismatch {
+            page_id,
+            expected,
+            found,
+        }
+    }
+
     /// Check if this is an I/O error
     pub fn is_io(&self) -> bool {
         matches!(self, Error::Io(_))
@@ -93,6 +124,16 @@ impl Error {
         matches!(self, Error::Corruption(_))
     }
 
+    /// Check if this is a checksum mismatch error
+    pub fn is_checksum_mismatch(&self) -> bool {
+        matches!(self, Error::ChecksumMismatch { .. })
+    }
+
+    /// Check if this is an invalid page type error
+    pub fn is_invalid_page_type(&self) -> bool {
+        matches!(self, Error::InvalidPageType(_))
+    }
+
     /// Check if this is a not found error
     pub fn is_not_found(&self) -> bool {
         matches!(self, Error::NotFound(_))
@@ -105,7 +146,11 @@ impl Error {
             | Error::TransactionConflict(_)
             | Error::InvalidInput(_)
             | Error::NotFound(_) => true,
-            Error::Corruption(_) | Error::OutOfMemory | Error::Internal(_) => false,
+            Error::Corruption(_)
+            | Error::OutOfMemory
+            | Error::Internal(_)
+            | Error::ChecksumMismatch { .. }
+            | Error::InvalidPageType(_) => false,
         }
     }
 }
@@ -145,6 +190,25 @@ mod tests {
         assert!(lumen_error.is_io());
     }
 
+    #[test]
+    fn test_checksum_mismatch_error() {
+        let error = Error::checksum_mismatch(42, 0xDEAD_BEEF, 0xCAFE_BABE);
+        assert!(error.is_checksum_mismatch());
+        assert!(!error.is_recoverable());
+        assert_eq!(
+            error.to_string(),
+            "Checksum mismatch for page 42: expected 0xdeadbeef, found 0xcafebabe"
+        );
+    }
+
+    #[test]
+    fn test_invalid_page_type_error() {
+        let error = Error::InvalidPageType(0xFF);
+        assert!(error.is_invalid_page_type());
+        assert!(!error.is_recoverable());
+        assert_eq!(error.to_string(), "Invalid page type: 0xff");
+    }
+
     #[test]
     fn test_result_type() {
         fn might_fail() -> Result<String> {
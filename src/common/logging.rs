@@ -116,9 +116,15 @@ macro_rules! lumen_trace {
 }
 
 /// Performance timing helper
+///
+/// A thin wrapper over [`crate::common::profiler::Span`]: besides logging
+/// a single elapsed duration on drop (and warning if it's slow), it also
+/// records a proper profiler span, so existing callers get full nested
+/// tracing for free.
 pub struct Timer {
     start: std::time::Instant,
     operation: String,
+    _span: crate::common::profiler::Span,
 }
 
 impl Timer {
@@ -128,6 +134,7 @@ impl Timer {
         lumen_debug!("Starting operation: {}", operation);
         Self {
             start: std::time::Instant::now(),
+            _span: crate::common::profiler::Span::start(operation.clone()),
             operation,
         }
     }
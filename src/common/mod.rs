@@ -1,7 +1,10 @@
 //! Common utilities and error handling for Lumen database
 
+#[cfg(feature = "track-allocations")]
+pub mod alloc_tracker;
 pub mod error;
 pub mod logging;
+pub mod profiler;
 
 pub mod test_utils;
 
@@ -0,0 +1,284 @@
+//! Lightweight self-profiler: nested spans recorded per-thread, with
+//! per-label aggregation and Chrome Trace Event JSON export.
+//!
+//! This complements [`crate::common::logging::Timer`] (kept as a thin
+//! wrapper over this module for existing callers) with a real profiling
+//! subsystem: RAII [`Span`] guards nest via a thread-local stack, and each
+//! recorded event carries the id of its enclosing span, so callers can
+//! reconstruct a full call tree instead of a flat list of durations.
+//! [`export_chrome_trace`] emits the recorded spans in Chrome's Trace
+//! Event Format, which loads directly into `chrome://tracing` or the
+//! Perfetto UI.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+/// Identifies a recorded span within its thread's event buffer.
+pub type SpanId = usize;
+
+/// A single recorded span: a named duration, optionally nested inside
+/// another recorded span on the same thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanRecord {
+    /// Name the span was started with.
+    pub label: String,
+    /// Numeric id of the thread that recorded this span.
+    pub thread_id: u64,
+    /// Nanoseconds since the process started profiling.
+    pub start_ns: u64,
+    /// Span duration in nanoseconds.
+    pub duration_ns: u64,
+    /// The enclosing span on the same thread, if any.
+    pub parent: Option<SpanId>,
+}
+
+/// Total time and invocation count accumulated for one label.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LabelStats {
+    /// The label these stats were aggregated for.
+    pub label: String,
+    /// Number of recorded spans with this label.
+    pub invocation_count: usize,
+    /// Sum of every recorded span's duration, in nanoseconds.
+    pub total_duration_ns: u64,
+}
+
+fn process_start() -> Instant {
+    static START: OnceLock<Instant> = OnceLock::new();
+    *START.get_or_init(Instant::now)
+}
+
+fn thread_numeric_id() -> u64 {
+    thread_local! {
+        static ID: u64 = next_thread_id();
+    }
+    ID.with(|id| *id)
+}
+
+fn next_thread_id() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    COUNTER.fetch_add(1, Ordering::Relaxed)
+}
+
+struct Profiler {
+    records: Vec<SpanRecord>,
+    stack: Vec<SpanId>,
+}
+
+impl Profiler {
+    fn new() -> Self {
+        Self {
+            records: Vec::new(),
+            stack: Vec::new(),
+        }
+    }
+}
+
+thread_local! {
+    static PROFILER: RefCell<Profiler> = RefCell::new(Profiler::new());
+}
+
+/// RAII guard recording one profiled span. Starting a span while another
+/// is already open on the same thread nests it as that span's child;
+/// dropping the guard closes the span and records its duration.
+#[must_use = "a Span records nothing until dropped; bind it to a variable"]
+pub struct Span {
+    id: SpanId,
+    start: Instant,
+}
+
+impl Span {
+    /// Begin recording a span labeled `label` on the current thread.
+    pub fn start<S: Into<String>>(label: S) -> Self {
+        let label = label.into();
+        let thread_id = thread_numeric_id();
+        let start = Instant::now();
+        #[allow(clippy::cast_possible_truncation)]
+        let start_ns = start.duration_since(process_start()).as_nanos() as u64;
+
+        let id = PROFILER.with(|profiler| {
+            let mut profiler = profiler.borrow_mut();
+            let parent = profiler.stack.last().copied();
+            let id = profiler.records.len();
+            profiler.records.push(SpanRecord {
+                label,
+                thread_id,
+                start_ns,
+                duration_ns: 0,
+                parent,
+            });
+            profiler.stack.push(id);
+            id
+        });
+
+        Self { id, start }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        #[allow(clippy::cast_possible_truncation)]
+        let duration_ns = self.start.elapsed().as_nanos() as u64;
+        PROFILER.with(|profiler| {
+            let mut profiler = profiler.borrow_mut();
+            profiler.records[self.id].duration_ns = duration_ns;
+            profiler.stack.pop();
+        });
+    }
+}
+
+/// Every span recorded so far on this thread, in the order they started.
+#[must_use]
+pub fn records() -> Vec<SpanRecord> {
+    PROFILER.with(|profiler| profiler.borrow().records.clone())
+}
+
+/// Number of spans recorded so far on this thread.
+#[must_use]
+pub fn record_count() -> usize {
+    PROFILER.with(|profiler| profiler.borrow().records.len())
+}
+
+/// Discard all spans recorded so far on this thread.
+pub fn clear() {
+    PROFILER.with(|profiler| {
+        let mut profiler = profiler.borrow_mut();
+        profiler.records.clear();
+        profiler.stack.clear();
+    });
+}
+
+/// Total time and invocation count per label, across this thread's
+/// recorded spans so far, sorted by label.
+#[must_use]
+pub fn aggregate() -> Vec<LabelStats> {
+    PROFILER.with(|profiler| {
+        let profiler = profiler.borrow();
+        let mut by_label: std::collections::HashMap<&str, LabelStats> =
+            std::collections::HashMap::new();
+
+        for record in &profiler.records {
+            let stats = by_label
+                .entry(record.label.as_str())
+                .or_insert_with(|| LabelStats {
+                    label: record.label.clone(),
+                    invocation_count: 0,
+                    total_duration_ns: 0,
+                });
+            stats.invocation_count += 1;
+            stats.total_duration_ns += record.duration_ns;
+        }
+
+        let mut stats: Vec<_> = by_label.into_values().collect();
+        stats.sort_by(|a, b| a.label.cmp(&b.label));
+        stats
+    })
+}
+
+fn escape_json(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Export this thread's recorded spans as a Chrome Trace Event Format
+/// JSON array (complete `"X"`-phase events), loadable directly into
+/// `chrome://tracing` or the Perfetto trace viewer.
+#[must_use]
+pub fn export_chrome_trace() -> String {
+    PROFILER.with(|profiler| {
+        let profiler = profiler.borrow();
+        let events: Vec<String> = profiler
+            .records
+            .iter()
+            .map(|record| {
+                format!(
+                    r#"{{"ph":"X","ts":{},"dur":{},"name":"{}","tid":{},"pid":0}}"#,
+                    record.start_ns / 1000,
+                    record.duration_ns / 1000,
+                    escape_json(&record.label),
+                    record.thread_id,
+                )
+            })
+            .collect();
+        format!("[{}]", events.join(","))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_records_duration() {
+        clear();
+        {
+            let _span = Span::start("work");
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+
+        let recorded = records();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].label, "work");
+        assert!(recorded[0].duration_ns >= 1_000_000);
+        assert_eq!(recorded[0].parent, None);
+    }
+
+    #[test]
+    fn test_nested_spans_record_parent() {
+        clear();
+        {
+            let _outer = Span::start("outer");
+            {
+                let _inner = Span::start("inner");
+            }
+        }
+
+        let recorded = records();
+        assert_eq!(recorded.len(), 2);
+        assert_eq!(recorded[0].label, "outer");
+        assert_eq!(recorded[0].parent, None);
+        assert_eq!(recorded[1].label, "inner");
+        assert_eq!(recorded[1].parent, Some(0));
+    }
+
+    #[test]
+    fn test_aggregate_sums_by_label() {
+        clear();
+        for _ in 0..3 {
+            let _span = Span::start("repeated");
+        }
+        let _other = Span::start("other");
+
+        let stats = aggregate();
+        let repeated = stats.iter().find(|s| s.label == "repeated").unwrap();
+        assert_eq!(repeated.invocation_count, 3);
+        let other = stats.iter().find(|s| s.label == "other").unwrap();
+        assert_eq!(other.invocation_count, 1);
+    }
+
+    #[test]
+    fn test_export_chrome_trace_is_well_formed_json_array() {
+        clear();
+        {
+            let _span = Span::start("traced");
+        }
+
+        let trace = export_chrome_trace();
+        assert!(trace.starts_with('['));
+        assert!(trace.ends_with(']'));
+        assert!(trace.contains(r#""ph":"X""#));
+        assert!(trace.contains(r#""name":"traced""#));
+    }
+
+    #[test]
+    fn test_clear_resets_thread_local_buffer() {
+        clear();
+        let _span = Span::start("temp");
+        drop(_span);
+        assert_eq!(record_count(), 1);
+
+        clear();
+        assert_eq!(record_count(), 0);
+    }
+}
@@ -76,6 +76,7 @@ impl Drop for TempDir {
 /// Memory usage tracker for tests
 pub struct MemoryTracker {
     initial_memory: usize,
+    initial_peak: usize,
     name: String,
 }
 
@@ -84,12 +85,14 @@ impl MemoryTracker {
     pub fn start<S: Into<String>>(name: S) -> Self {
         let name = name.into();
         let initial_memory = get_memory_usage();
+        let initial_peak = get_peak_memory_usage();
         println!(
             "Memory tracker '{name}' started at {initial_memory} bytes"
         );
 
         Self {
             initial_memory,
+            initial_peak,
             name,
         }
     }
@@ -101,24 +104,55 @@ impl MemoryTracker {
         current as isize - self.initial_memory as isize
     }
 
+    /// Bytes by which the peak heap usage has grown since tracking
+    /// started.
+    pub fn peak_delta(&self) -> usize {
+        get_peak_memory_usage().saturating_sub(self.initial_peak)
+    }
+
     /// Stop tracking and return memory delta
     pub fn stop(self) -> isize {
         let delta = self.current_delta();
         println!(
-            "Memory tracker '{}' ended with delta: {} bytes",
-            self.name, delta
+            "Memory tracker '{}' ended with delta: {} bytes, peak delta: {} bytes",
+            self.name,
+            delta,
+            self.peak_delta()
         );
         delta
     }
 }
 
+/// Bytes currently allocated on the heap.
+///
+/// With the `track-allocations` feature enabled, this reads the atomic
+/// counters [`crate::common::alloc_tracker::TrackingAllocator`] maintains
+/// as the process's global allocator. Without it, there's no allocator
+/// hook installed, so this falls back to the previous placeholder
+/// approximation (constant, and thus useless for deltas).
 fn get_memory_usage() -> usize {
-    // Simple memory usage approximation
-    // In a real implementation, we'd use platform-specific APIs
-    use std::alloc::System;
+    #[cfg(feature = "track-allocations")]
+    {
+        crate::common::alloc_tracker::current_bytes()
+    }
+    #[cfg(not(feature = "track-allocations"))]
+    {
+        use std::alloc::System;
+        std::mem::size_of::<System>() // Placeholder
+    }
+}
 
-    // This is a simplified version - in practice we'd track allocations
-    std::mem::size_of::<System>() // Placeholder
+/// Highest heap usage ever recorded. See [`get_memory_usage`] for the
+/// same feature-gated caveat.
+fn get_peak_memory_usage() -> usize {
+    #[cfg(feature = "track-allocations")]
+    {
+        crate::common::alloc_tracker::peak_bytes()
+    }
+    #[cfg(not(feature = "track-allocations"))]
+    {
+        get_memory_usage()
+    }
 }
 
 /// Performance assertion helper
@@ -24,6 +24,15 @@ pub mod types;
 // Re-exports for convenience
 pub use common::{Error, Result};
 
+/// Installs [`common::alloc_tracker::TrackingAllocator`] as the process's
+/// global allocator so [`common::test_utils::MemoryTracker`] can report
+/// real heap deltas instead of a placeholder. See the `track-allocations`
+/// feature.
+#[cfg(feature = "track-allocations")]
+#[global_allocator]
+static GLOBAL_ALLOCATOR: common::alloc_tracker::TrackingAllocator =
+    common::alloc_tracker::TrackingAllocator;
+
 /// Version information
 pub const VERSION_MAJOR: u32 = 0;
 /// Version information
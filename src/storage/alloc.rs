@@ -0,0 +1,343 @@
+//! Free-page allocator backed by the `FreeList` page type
+//!
+//! Persists the set of free pages as a singly linked chain of `FreeList`
+//! pages. Each free-list page's body holds a `next` `PageId` pointer, an
+//! entry count, and a packed array of reclaimed `PageId`s sized from
+//! `PAGE_USABLE_SIZE`. `alloc_page` pops an id from the head free-list
+//! page, falling back to extending the file past the current maximum
+//! `PageId` when the free list is empty. `free_page` pushes an id onto the
+//! head page, spilling to a fresh free-list page (the freed page itself)
+//! when the head is full.
+
+use crate::common::error::Error;
+use crate::storage::page::Page;
+use crate::storage::page_constants::{PageId, INVALID_PAGE_ID, PAGE_USABLE_SIZE};
+use crate::storage::page_io::{read_page_from_file, write_page_to_file};
+use crate::storage::page_type::PageType;
+use std::fs::File;
+
+const PAGE_ID_SIZE: usize = std::mem::size_of::<PageId>();
+const COUNT_SIZE: usize = std::mem::size_of::<u32>();
+const FREE_LIST_BODY_HEADER_SIZE: usize = PAGE_ID_SIZE + COUNT_SIZE;
+
+/// How many reclaimed `PageId` entries fit in one free-list page.
+pub const FREE_LIST_ENTRIES_PER_PAGE: usize =
+    (PAGE_USABLE_SIZE - FREE_LIST_BODY_HEADER_SIZE) / PAGE_ID_SIZE;
+
+/// Allocates and frees persistent pages, backed by a chain of `FreeList`
+/// pages stored in the database file.
+pub struct PageAllocator {
+    free_list_head: PageId,
+    next_new_page_id: PageId,
+}
+
+impl PageAllocator {
+    /// Create an allocator with an empty free list, handing out fresh
+    /// page ids starting after `max_allocated_page_id`.
+    pub fn new(max_allocated_page_id: PageId) -> Self {
+        Self {
+            free_list_head: INVALID_PAGE_ID,
+            next_new_page_id: max_allocated_page_id + 1,
+        }
+    }
+
+    /// Resume an allocator whose free-list chain already starts at
+    /// `free_list_head` (e.g. recovered from a database header).
+    pub fn resume(free_list_head: PageId, next_new_page_id: PageId) -> Self {
+        Self {
+            free_list_head,
+            next_new_page_id,
+        }
+    }
+
+    /// The page id of the head free-list page, or `INVALID_PAGE_ID` if the
+    /// free list is empty. Useful for persisting allocator state.
+    pub fn free_list_head(&self) -> PageId {
+        self.free_list_head
+    }
+
+    /// The next page id that would be handed out by extending the file.
+    pub fn next_new_page_id(&self) -> PageId {
+        self.next_new_page_id
+    }
+
+    /// Count how many pages are currently free, by walking the chain.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a free-list page can't be read.
+    pub fn free_page_count(&self, file: &mut File) -> Result<usize, Error> {
+        let mut count = 0;
+        let mut current = self.free_list_head;
+        while current != INVALID_PAGE_ID {
+            let page = read_page_from_file(file, u64::from(current))?;
+            // The chain page itself occupies a free slot in addition to
+            // the reclaimed ids it carries.
+            count += 1 + read_entries(&page).len();
+            current = read_next(&page);
+        }
+        Ok(count)
+    }
+
+    /// Allocate a page of the given type.
+    ///
+    /// Pops a reclaimed id from the head free-list page when one is
+    /// available, otherwise extends the file past the current maximum
+    /// page id.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Internal` if the page id space is exhausted, and
+    /// propagates I/O errors otherwise.
+    pub fn alloc_page(&mut self, file: &mut File, page_type: PageType) -> Result<PageId, Error> {
+        if self.free_list_head != INVALID_PAGE_ID {
+            return self.alloc_from_free_list(file, page_type);
+        }
+        self.alloc_new(file, page_type)
+    }
+
+    fn alloc_from_free_list(
+        &mut self,
+        file: &mut File,
+        page_type: PageType,
+    ) -> Result<PageId, Error> {
+        let head_id = self.free_list_head;
+        let mut head_page = read_page_from_file(file, u64::from(head_id))?;
+        let mut entries = read_entries(&head_page);
+
+        if let Some(reused) = entries.pop() {
+            write_entries(&mut head_page, &entries);
+            head_page.calculate_checksum()?;
+            write_page_to_file(file, u64::from(head_id), &head_page)?;
+
+            return self.initialize_page(file, reused, page_type);
+        }
+
+        // The head page itself is empty: reuse it as the allocated page
+        // and promote its `next` pointer to be the new free-list head.
+        self.free_list_head = read_next(&head_page);
+        self.initialize_page(file, head_id, page_type)
+    }
+
+    fn alloc_new(&mut self, file: &mut File, page_type: PageType) -> Result<PageId, Error> {
+        let new_id = self.next_new_page_id;
+        if new_id == INVALID_PAGE_ID {
+            return Err(Error::internal("page id space exhausted"));
+        }
+        self.next_new_page_id = self
+            .next_new_page_id
+            .checked_add(1)
+            .ok_or_else(|| Error::internal("page id space exhausted"))?;
+
+        self.initialize_page(file, new_id, page_type)
+    }
+
+    fn initialize_page(
+        &self,
+        file: &mut File,
+        page_id: PageId,
+        page_type: PageType,
+    ) -> Result<PageId, Error> {
+        let mut page = Page::new();
+        page.header_mut().page_type = page_type;
+        page.header_mut().page_id = page_id;
+        page.calculate_checksum()?;
+        write_page_to_file(file, u64::from(page_id), &page)?;
+        Ok(page_id)
+    }
+
+    /// Return `page_id` to the free list.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if `page_id` is `INVALID_PAGE_ID` or
+    /// is already present in the free list (double free), and propagates
+    /// I/O errors otherwise.
+    pub fn free_page(&mut self, file: &mut File, page_id: PageId) -> Result<(), Error> {
+        if page_id == INVALID_PAGE_ID {
+            return Err(Error::invalid_input("cannot free the invalid page id"));
+        }
+        if self.is_free(file, page_id)? {
+            return Err(Error::invalid_input(format!(
+                "page {page_id} is already free (double free)"
+            )));
+        }
+
+        if self.free_list_head != INVALID_PAGE_ID {
+            let head_id = self.free_list_head;
+            let mut head_page = read_page_from_file(file, u64::from(head_id))?;
+            let mut entries = read_entries(&head_page);
+
+            if entries.len() < FREE_LIST_ENTRIES_PER_PAGE {
+                entries.push(page_id);
+                write_entries(&mut head_page, &entries);
+                head_page.calculate_checksum()?;
+                write_page_to_file(file, u64::from(head_id), &head_page)?;
+                return Ok(());
+            }
+        }
+
+        // The head page is full (or there is no head yet): the freed page
+        // itself becomes a new, empty free-list page chained in front.
+        let mut new_head = Page::new();
+        new_head.header_mut().page_type = PageType::FreeList;
+        new_head.header_mut().page_id = page_id;
+        write_next(&mut new_head, self.free_list_head);
+        write_entries(&mut new_head, &[]);
+        new_head.calculate_checksum()?;
+        write_page_to_file(file, u64::from(page_id), &new_head)?;
+        self.free_list_head = page_id;
+        Ok(())
+    }
+
+    /// Whether `page_id` is currently in the free list, either as a
+    /// free-list page itself or as a reclaimed entry within one.
+    fn is_free(&self, file: &mut File, page_id: PageId) -> Result<bool, Error> {
+        let mut current = self.free_list_head;
+        while current != INVALID_PAGE_ID {
+            if current == page_id {
+                return Ok(true);
+            }
+            let page = read_page_from_file(file, u64::from(current))?;
+            if read_entries(&page).contains(&page_id) {
+                return Ok(true);
+            }
+            current = read_next(&page);
+        }
+        Ok(false)
+    }
+}
+
+fn read_next(page: &Page) -> PageId {
+    let bytes: [u8; PAGE_ID_SIZE] = page.data()[..PAGE_ID_SIZE]
+        .try_into()
+        .expect("slice has exactly PAGE_ID_SIZE bytes");
+    PageId::from_le_bytes(bytes)
+}
+
+fn write_next(page: &mut Page, next: PageId) {
+    page.data_mut()[..PAGE_ID_SIZE].copy_from_slice(&next.to_le_bytes());
+}
+
+fn read_entries(page: &Page) -> Vec<PageId> {
+    let body = page.data();
+    let count = u32::from_le_bytes(
+        body[PAGE_ID_SIZE..FREE_LIST_BODY_HEADER_SIZE]
+            .try_into()
+            .expect("slice has exactly COUNT_SIZE bytes"),
+    ) as usize;
+
+    body[FREE_LIST_BODY_HEADER_SIZE..]
+        .chunks_exact(PAGE_ID_SIZE)
+        .take(count)
+        .map(|chunk| PageId::from_le_bytes(chunk.try_into().expect("chunk is PAGE_ID_SIZE bytes")))
+        .collect()
+}
+
+fn write_entries(page: &mut Page, entries: &[PageId]) {
+    debug_assert!(entries.len() <= FREE_LIST_ENTRIES_PER_PAGE);
+
+    let body = page.data_mut();
+    #[allow(clippy::cast_possible_truncation)]
+    let count = entries.len() as u32;
+    body[PAGE_ID_SIZE..FREE_LIST_BODY_HEADER_SIZE].copy_from_slice(&count.to_le_bytes());
+
+    let entries_region = &mut body[FREE_LIST_BODY_HEADER_SIZE..];
+    for (chunk, &entry) in entries_region.chunks_exact_mut(PAGE_ID_SIZE).zip(entries) {
+        chunk.copy_from_slice(&entry.to_le_bytes());
+    }
+    for chunk in entries_region
+        .chunks_exact_mut(PAGE_ID_SIZE)
+        .skip(entries.len())
+    {
+        chunk.fill(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn open_temp_file() -> File {
+        let temp = NamedTempFile::new().unwrap();
+        File::options()
+            .read(true)
+            .write(true)
+            .open(temp.path())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_alloc_extends_file_when_free_list_empty() {
+        let mut file = open_temp_file();
+        let mut allocator = PageAllocator::new(0);
+
+        let first = allocator.alloc_page(&mut file, PageType::Data).unwrap();
+        let second = allocator.alloc_page(&mut file, PageType::Data).unwrap();
+
+        assert_eq!(first, 1);
+        assert_eq!(second, 2);
+    }
+
+    #[test]
+    fn test_free_then_alloc_reuses_page() {
+        let mut file = open_temp_file();
+        let mut allocator = PageAllocator::new(0);
+
+        let page_id = allocator.alloc_page(&mut file, PageType::Data).unwrap();
+        allocator.free_page(&mut file, page_id).unwrap();
+        assert_eq!(allocator.free_page_count(&mut file).unwrap(), 1);
+
+        let reused = allocator.alloc_page(&mut file, PageType::BTreeLeaf).unwrap();
+        assert_eq!(reused, page_id);
+        assert_eq!(allocator.free_page_count(&mut file).unwrap(), 0);
+
+        let page = read_page_from_file(&mut file, u64::from(reused)).unwrap();
+        assert_eq!(page.header().page_type, PageType::BTreeLeaf);
+    }
+
+    #[test]
+    fn test_double_free_is_rejected() {
+        let mut file = open_temp_file();
+        let mut allocator = PageAllocator::new(0);
+
+        let page_id = allocator.alloc_page(&mut file, PageType::Data).unwrap();
+        allocator.free_page(&mut file, page_id).unwrap();
+
+        let result = allocator.free_page(&mut file, page_id);
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_freeing_invalid_page_id_is_rejected() {
+        let mut file = open_temp_file();
+        let mut allocator = PageAllocator::new(0);
+
+        let result = allocator.free_page(&mut file, INVALID_PAGE_ID);
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_free_list_spills_to_new_page_when_full() {
+        let mut file = open_temp_file();
+        let mut allocator = PageAllocator::new(0);
+
+        let mut allocated = Vec::new();
+        for _ in 0..FREE_LIST_ENTRIES_PER_PAGE + 2 {
+            allocated.push(allocator.alloc_page(&mut file, PageType::Data).unwrap());
+        }
+
+        for &id in &allocated {
+            allocator.free_page(&mut file, id).unwrap();
+        }
+
+        assert_eq!(
+            allocator.free_page_count(&mut file).unwrap(),
+            allocated.len()
+        );
+
+        // The head free-list page should itself be one of the freed pages.
+        assert!(allocated.contains(&allocator.free_list_head()));
+    }
+}
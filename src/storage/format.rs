@@ -0,0 +1,122 @@
+//! On-disk storage format descriptor and validation
+//!
+//! Page size and page-id width (see
+//! [`crate::storage::page_constants`]) are compile-time choices, but a
+//! database file is created once under a particular build's settings and
+//! must not silently be reopened under another. [`StorageFormat`] records
+//! the compiled-in parameters and is stamped into the header page (page 0)
+//! so a mismatched reopen is rejected before the rest of the file is ever
+//! touched.
+
+use crate::common::error::Error;
+use crate::storage::page::Page;
+use crate::storage::page_constants::{PAGE_ID_BITS, PAGE_SIZE};
+
+const FORMAT_MAGIC: u32 = 0x4C55_4D4E; // "LUMN"
+
+/// Describes the page-size and page-id-width mode a database was created
+/// under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageFormat {
+    /// Page size in bytes (see [`crate::storage::page_constants::PAGE_SIZE`])
+    pub page_size: usize,
+    /// Bits used to represent a page ID (32 or 64)
+    pub page_id_bits: u8,
+}
+
+impl StorageFormat {
+    /// The format this build was compiled for.
+    #[must_use]
+    pub fn current() -> Self {
+        Self {
+            page_size: PAGE_SIZE,
+            page_id_bits: PAGE_ID_BITS,
+        }
+    }
+
+    /// Stamp this format into the first bytes of the header page's data
+    /// area.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn write_to(self, page: &mut Page) {
+        let data = page.data_mut();
+        data[0..4].copy_from_slice(&FORMAT_MAGIC.to_le_bytes());
+        data[4..8].copy_from_slice(&(self.page_size as u32).to_le_bytes());
+        data[8] = self.page_id_bits;
+    }
+
+    /// Decode and validate a format previously stamped with
+    /// [`Self::write_to`] against this build's compiled-in
+    /// [`Self::current`] format.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Corruption`] if the header page doesn't carry a
+    /// recognizable format stamp, and [`Error::InvalidInput`] if it does but
+    /// the page size or page-id width don't match this build.
+    pub fn read_and_validate(page: &Page) -> Result<Self, Error> {
+        let data = page.data();
+        let magic = u32::from_le_bytes(data[0..4].try_into().expect("4 bytes"));
+        if magic != FORMAT_MAGIC {
+            return Err(Error::corruption(
+                "header page does not contain a storage format stamp",
+            ));
+        }
+
+        let page_size = u32::from_le_bytes(data[4..8].try_into().expect("4 bytes")) as usize;
+        let page_id_bits = data[8];
+        let format = Self {
+            page_size,
+            page_id_bits,
+        };
+
+        let current = Self::current();
+        if format != current {
+            return Err(Error::invalid_input(format!(
+                "database was created with page_size={}, page_id_bits={} \
+                 but this build uses page_size={}, page_id_bits={}",
+                format.page_size, format.page_id_bits, current.page_size, current.page_id_bits
+            )));
+        }
+
+        Ok(format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current_matches_compiled_constants() {
+        let format = StorageFormat::current();
+        assert_eq!(format.page_size, PAGE_SIZE);
+        assert_eq!(format.page_id_bits, PAGE_ID_BITS);
+    }
+
+    #[test]
+    fn test_roundtrip_write_and_validate() {
+        let mut page = Page::new();
+        StorageFormat::current().write_to(&mut page);
+
+        let validated = StorageFormat::read_and_validate(&page).unwrap();
+        assert_eq!(validated, StorageFormat::current());
+    }
+
+    #[test]
+    fn test_rejects_missing_stamp() {
+        let page = Page::new();
+        let err = StorageFormat::read_and_validate(&page).unwrap_err();
+        assert!(err.is_corruption());
+    }
+
+    #[test]
+    fn test_rejects_mismatched_page_size() {
+        let mut page = Page::new();
+        let mut format = StorageFormat::current();
+        format.page_size += 1;
+        format.write_to(&mut page);
+
+        let err = StorageFormat::read_and_validate(&page).unwrap_err();
+        assert!(matches!(err, Error::InvalidInput(_)));
+    }
+}
@@ -0,0 +1,416 @@
+//! Crash-safe database super-header with dual alternating commit slots
+//!
+//! Page 0 of every database file is reserved for the header page.
+//! [`crate::storage::format::StorageFormat`] already stamps its magic,
+//! page size, and page-id width into the first 9 bytes of that page's
+//! data area (after the page header);
+//! this module picks up right after that stamp with its own preamble
+//! (magic, format version, and the count of allocated pages) followed by
+//! two identically-laid-out commit slots. Each slot records a snapshot
+//! of the committed state — the root page id, the free-list root page
+//! id, and the transaction id that produced it — plus its own checksum
+//! over the rest of the slot's bytes.
+//!
+//! A commit never overwrites the slot a reader might currently be
+//! trusting: it writes the *other* slot with the incremented transaction
+//! id, `fsync`s, and only then is that slot authoritative. On open,
+//! [`recover_latest_slot`] reads both slots, discards any whose checksum
+//! doesn't verify (a crash can leave at most one slot mid-write), and
+//! picks the valid slot with the higher transaction id. A crash partway
+//! through a commit therefore always recovers to either the old state or
+//! the new one, never a torn mix of both.
+
+use crate::common::error::Error;
+use crate::storage::checksum::crc32c;
+use crate::storage::page::Page;
+use crate::storage::page_constants::PageId;
+
+const HEADER_MAGIC: u32 = 0x4C55_4D48; // "LUMH"
+const HEADER_VERSION: u8 = 1;
+
+// StorageFormat::write_to occupies bytes [0, 9) of the header page's data
+// area (offsets here are all into `Page::data`); start
+// this section on the next 8-byte-aligned offset to leave it untouched.
+const SECTION_OFFSET: usize = 16;
+
+const MAGIC_SIZE: usize = std::mem::size_of::<u32>();
+const VERSION_SIZE: usize = std::mem::size_of::<u8>();
+const PAGE_SIZE_FIELD_SIZE: usize = std::mem::size_of::<u32>();
+const ALLOCATED_PAGES_SIZE: usize = std::mem::size_of::<u64>();
+
+const MAGIC_OFFSET: usize = SECTION_OFFSET;
+const VERSION_OFFSET: usize = MAGIC_OFFSET + MAGIC_SIZE;
+const PAGE_SIZE_OFFSET: usize = VERSION_OFFSET + VERSION_SIZE;
+const ALLOCATED_PAGES_OFFSET: usize = PAGE_SIZE_OFFSET + PAGE_SIZE_FIELD_SIZE;
+const PREAMBLE_END: usize = ALLOCATED_PAGES_OFFSET + ALLOCATED_PAGES_SIZE;
+
+const PAGE_ID_SIZE: usize = std::mem::size_of::<PageId>();
+const TXN_ID_SIZE: usize = std::mem::size_of::<u64>();
+const SLOT_CHECKSUM_SIZE: usize = std::mem::size_of::<u32>();
+const SLOT_SIZE: usize = PAGE_ID_SIZE * 2 + TXN_ID_SIZE + SLOT_CHECKSUM_SIZE;
+
+const SLOT_COUNT: usize = 2;
+
+fn slot_offset(slot_index: usize) -> usize {
+    PREAMBLE_END + slot_index * SLOT_SIZE
+}
+
+/// The super-header's fixed preamble, stamped into page 0 ahead of the
+/// two commit slots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SuperHeader {
+    /// Format version of this super-header layout.
+    pub version: u8,
+    /// Page size this database was created with, in bytes.
+    pub page_size: u32,
+    /// Count of pages ever allocated in this database file.
+    pub allocated_pages: u64,
+}
+
+/// A committed snapshot of database state: one of the two alternating
+/// commit slots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CommitSlot {
+    /// Root page id of the primary data/index structure.
+    pub root_page_id: PageId,
+    /// Root page id of the free-list chain (see
+    /// [`crate::storage::alloc::PageAllocator`]).
+    pub freelist_root_page_id: PageId,
+    /// Transaction id this slot was committed with. Recovery picks the
+    /// valid slot with the higher id.
+    pub transaction_id: u64,
+}
+
+/// Stamp the super-header preamble into page 0's data area.
+#[allow(clippy::cast_possible_truncation)]
+pub fn write_header(page: &mut Page, header: SuperHeader) {
+    let data = page.data_mut();
+    data[MAGIC_OFFSET..MAGIC_OFFSET + MAGIC_SIZE].copy_from_slice(&HEADER_MAGIC.to_le_bytes());
+    data[VERSION_OFFSET] = header.version;
+    data[PAGE_SIZE_OFFSET..PAGE_SIZE_OFFSET + PAGE_SIZE_FIELD_SIZE]
+        .copy_from_slice(&header.page_size.to_le_bytes());
+    data[ALLOCATED_PAGES_OFFSET..ALLOCATED_PAGES_OFFSET + ALLOCATED_PAGES_SIZE]
+        .copy_from_slice(&header.allocated_pages.to_le_bytes());
+}
+
+/// Stamp the current build's super-header preamble (version
+/// [`HEADER_VERSION`], actual [`crate::storage::page_constants::PAGE_SIZE`])
+/// into page 0, with the given allocated page count.
+pub fn write_current_header(page: &mut Page, allocated_pages: u64) {
+    #[allow(clippy::cast_possible_truncation)]
+    write_header(
+        page,
+        SuperHeader {
+            version: HEADER_VERSION,
+            page_size: crate::storage::page_constants::PAGE_SIZE as u32,
+            allocated_pages,
+        },
+    );
+}
+
+/// Read and validate the super-header preamble previously stamped with
+/// [`write_header`].
+///
+/// # Errors
+///
+/// Returns [`Error::Corruption`] if page 0 doesn't carry a recognizable
+/// super-header magic number.
+pub fn read_header(page: &Page) -> Result<SuperHeader, Error> {
+    let data = page.data();
+    let magic = u32::from_le_bytes(
+        data[MAGIC_OFFSET..MAGIC_OFFSET + MAGIC_SIZE]
+            .try_into()
+            .expect("4 bytes"),
+    );
+    if magic != HEADER_MAGIC {
+        return Err(Error::corruption(
+            "page 0 does not contain a recognizable super-header magic number",
+        ));
+    }
+
+    let version = data[VERSION_OFFSET];
+    let page_size = u32::from_le_bytes(
+        data[PAGE_SIZE_OFFSET..PAGE_SIZE_OFFSET + PAGE_SIZE_FIELD_SIZE]
+            .try_into()
+            .expect("4 bytes"),
+    );
+    let allocated_pages = u64::from_le_bytes(
+        data[ALLOCATED_PAGES_OFFSET..ALLOCATED_PAGES_OFFSET + ALLOCATED_PAGES_SIZE]
+            .try_into()
+            .expect("8 bytes"),
+    );
+
+    Ok(SuperHeader {
+        version,
+        page_size,
+        allocated_pages,
+    })
+}
+
+/// Write one of the two commit slots (`slot_index` 0 or 1), computing and
+/// storing its checksum over the rest of the slot's bytes.
+///
+/// Callers implementing two-phase commit should write the *inactive*
+/// slot (the one that didn't win the last [`recover_latest_slot`]) with
+/// `slot.transaction_id` incremented, then `fsync` the file so the new
+/// slot only becomes authoritative once it's durable.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidInput`] if `slot_index` is not 0 or 1.
+pub fn write_commit_slot(page: &mut Page, slot_index: usize, slot: CommitSlot) -> Result<(), Error> {
+    if slot_index >= SLOT_COUNT {
+        return Err(Error::invalid_input(format!(
+            "commit slot index must be 0 or 1, got {slot_index}"
+        )));
+    }
+
+    let offset = slot_offset(slot_index);
+    let region = &mut page.data_mut()[offset..offset + SLOT_SIZE];
+    region[..PAGE_ID_SIZE].copy_from_slice(&slot.root_page_id.to_le_bytes());
+    region[PAGE_ID_SIZE..PAGE_ID_SIZE * 2].copy_from_slice(&slot.freelist_root_page_id.to_le_bytes());
+    region[PAGE_ID_SIZE * 2..PAGE_ID_SIZE * 2 + TXN_ID_SIZE]
+        .copy_from_slice(&slot.transaction_id.to_le_bytes());
+    region[PAGE_ID_SIZE * 2 + TXN_ID_SIZE..].fill(0);
+
+    let checksum = crc32c(region);
+    let region = &mut page.data_mut()[offset..offset + SLOT_SIZE];
+    region[PAGE_ID_SIZE * 2 + TXN_ID_SIZE..].copy_from_slice(&checksum.to_le_bytes());
+
+    Ok(())
+}
+
+/// Read one of the two commit slots, verifying its checksum.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidInput`] if `slot_index` is not 0 or 1, and
+/// [`Error::Corruption`] if the slot's stored checksum doesn't match its
+/// bytes (e.g. a crash interrupted writing this slot).
+pub fn read_commit_slot(page: &Page, slot_index: usize) -> Result<CommitSlot, Error> {
+    if slot_index >= SLOT_COUNT {
+        return Err(Error::invalid_input(format!(
+            "commit slot index must be 0 or 1, got {slot_index}"
+        )));
+    }
+
+    let offset = slot_offset(slot_index);
+    let region = &page.data()[offset..offset + SLOT_SIZE];
+
+    let stored_checksum = u32::from_le_bytes(
+        region[PAGE_ID_SIZE * 2 + TXN_ID_SIZE..]
+            .try_into()
+            .expect("4 bytes"),
+    );
+    let mut scratch = region.to_vec();
+    scratch[PAGE_ID_SIZE * 2 + TXN_ID_SIZE..].fill(0);
+    let computed_checksum = crc32c(&scratch);
+
+    if stored_checksum != computed_checksum {
+        return Err(Error::corruption(format!(
+            "commit slot {slot_index} checksum mismatch: expected {stored_checksum:#010x}, found {computed_checksum:#010x}"
+        )));
+    }
+
+    let root_page_id = PageId::from_le_bytes(region[..PAGE_ID_SIZE].try_into().expect("slice"));
+    let freelist_root_page_id =
+        PageId::from_le_bytes(region[PAGE_ID_SIZE..PAGE_ID_SIZE * 2].try_into().expect("slice"));
+    let transaction_id = u64::from_le_bytes(
+        region[PAGE_ID_SIZE * 2..PAGE_ID_SIZE * 2 + TXN_ID_SIZE]
+            .try_into()
+            .expect("slice"),
+    );
+
+    Ok(CommitSlot {
+        root_page_id,
+        freelist_root_page_id,
+        transaction_id,
+    })
+}
+
+/// Recover the authoritative commit slot: the valid slot (checksum
+/// verifies) with the higher transaction id.
+///
+/// # Errors
+///
+/// Returns [`Error::Corruption`] if neither slot's checksum verifies.
+pub fn recover_latest_slot(page: &Page) -> Result<CommitSlot, Error> {
+    let slots: Vec<CommitSlot> = (0..SLOT_COUNT).filter_map(|i| read_commit_slot(page, i).ok()).collect();
+
+    slots
+        .into_iter()
+        .max_by_key(|slot| slot.transaction_id)
+        .ok_or_else(|| Error::corruption("both commit slots are invalid: cannot recover database state"))
+}
+
+/// Which slot index [`recover_latest_slot`] would currently treat as
+/// authoritative, so callers can write a commit to the *other* one.
+///
+/// Returns 0 if neither slot is currently valid (a fresh database).
+#[must_use]
+pub fn inactive_slot_index(page: &Page) -> usize {
+    match (read_commit_slot(page, 0), read_commit_slot(page, 1)) {
+        (Ok(a), Ok(b)) => {
+            if a.transaction_id >= b.transaction_id {
+                1
+            } else {
+                0
+            }
+        }
+        (Ok(_), Err(_)) => 1,
+        (Err(_), Ok(_)) => 0,
+        (Err(_), Err(_)) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_roundtrip() {
+        let mut page = Page::new();
+        write_current_header(&mut page, 7);
+
+        let header = read_header(&page).unwrap();
+        assert_eq!(header.version, HEADER_VERSION);
+        assert_eq!(
+            header.page_size,
+            crate::storage::page_constants::PAGE_SIZE as u32
+        );
+        assert_eq!(header.allocated_pages, 7);
+    }
+
+    #[test]
+    fn test_read_header_rejects_missing_magic() {
+        let page = Page::new();
+        let err = read_header(&page).unwrap_err();
+        assert!(err.is_corruption());
+    }
+
+    #[test]
+    fn test_commit_slot_roundtrip() {
+        let mut page = Page::new();
+        let slot = CommitSlot {
+            root_page_id: 10,
+            freelist_root_page_id: 20,
+            transaction_id: 1,
+        };
+        write_commit_slot(&mut page, 0, slot).unwrap();
+
+        let read_back = read_commit_slot(&page, 0).unwrap();
+        assert_eq!(read_back, slot);
+    }
+
+    #[test]
+    fn test_invalid_slot_index_is_rejected() {
+        let mut page = Page::new();
+        let slot = CommitSlot::default();
+        assert!(matches!(
+            write_commit_slot(&mut page, 2, slot),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            read_commit_slot(&page, 2),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_recover_picks_higher_transaction_id() {
+        let mut page = Page::new();
+        write_commit_slot(
+            &mut page,
+            0,
+            CommitSlot {
+                root_page_id: 1,
+                freelist_root_page_id: 2,
+                transaction_id: 5,
+            },
+        )
+        .unwrap();
+        write_commit_slot(
+            &mut page,
+            1,
+            CommitSlot {
+                root_page_id: 3,
+                freelist_root_page_id: 4,
+                transaction_id: 6,
+            },
+        )
+        .unwrap();
+
+        let recovered = recover_latest_slot(&page).unwrap();
+        assert_eq!(recovered.transaction_id, 6);
+        assert_eq!(recovered.root_page_id, 3);
+    }
+
+    #[test]
+    fn test_recover_discards_slot_with_bad_checksum() {
+        let mut page = Page::new();
+        write_commit_slot(
+            &mut page,
+            0,
+            CommitSlot {
+                root_page_id: 1,
+                freelist_root_page_id: 2,
+                transaction_id: 5,
+            },
+        )
+        .unwrap();
+        write_commit_slot(
+            &mut page,
+            1,
+            CommitSlot {
+                root_page_id: 3,
+                freelist_root_page_id: 4,
+                transaction_id: 9,
+            },
+        )
+        .unwrap();
+
+        // Corrupt slot 1, which otherwise has the higher transaction id.
+        let offset = slot_offset(1);
+        page.data_mut()[offset] ^= 0xFF;
+
+        let recovered = recover_latest_slot(&page).unwrap();
+        assert_eq!(recovered.transaction_id, 5);
+    }
+
+    #[test]
+    fn test_recover_fails_when_both_slots_invalid() {
+        let page = Page::new();
+        let err = recover_latest_slot(&page).unwrap_err();
+        assert!(err.is_corruption());
+    }
+
+    #[test]
+    fn test_inactive_slot_alternates_with_transaction_id() {
+        let mut page = Page::new();
+        assert_eq!(inactive_slot_index(&page), 0);
+
+        write_commit_slot(
+            &mut page,
+            0,
+            CommitSlot {
+                root_page_id: 1,
+                freelist_root_page_id: 1,
+                transaction_id: 1,
+            },
+        )
+        .unwrap();
+        assert_eq!(inactive_slot_index(&page), 1);
+
+        write_commit_slot(
+            &mut page,
+            1,
+            CommitSlot {
+                root_page_id: 2,
+                freelist_root_page_id: 2,
+                transaction_id: 2,
+            },
+        )
+        .unwrap();
+        assert_eq!(inactive_slot_index(&page), 0);
+    }
+}
@@ -0,0 +1,47 @@
+//! Former best-effort hardware fault guard for memory-mapped page access
+//!
+//! This used to install a process-wide SIGBUS/SIGSEGV handler and
+//! `longjmp` back out of a faulting guarded closure. That relied on
+//! calling `setjmp` from ordinary Rust code and `longjmp`ing out of a
+//! signal handler - both require LLVM's `returns_twice` function
+//! attribute on whatever calls `setjmp`, and there is no stable way to
+//! attach that through a plain `extern "C"` declaration. That made the
+//! whole mechanism undefined behavior regardless of how carefully the
+//! rest of it (address-range checks, previous-handler chaining, and so
+//! on) was written, not a "best-effort, worst case it doesn't catch
+//! everything" trade-off. It was also never actually exercised by any
+//! test - nothing in this crate raised a real SIGBUS/SIGSEGV to go
+//! through it - so it shipped unsound FFI for a benefit nothing verified.
+//!
+//! It has been removed. `read_page_mmap`/`write_page_mmap`'s own bounds
+//! check against the mapping's actual file length already prevents every
+//! currently-known fault trigger in this codebase (an empty file, a
+//! short file, or reading past EOF); that bounds check is the only guard
+//! that remains. [`with_fault_guard`] is kept as a deliberate no-op
+//! passthrough, rather than deleted outright, so its callers don't need
+//! their own conditional compilation for platforms that never had a real
+//! guard to begin with.
+
+/// Run `f`. This no longer does anything beyond calling `f` directly -
+/// see the module docs for why the signal-handler-based guard this used
+/// to wrap was removed.
+pub fn with_fault_guard<T>(f: impl FnOnce() -> T) -> T {
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fault_guard_passes_through_normal_result() {
+        assert_eq!(with_fault_guard(|| 2 + 2), 4);
+    }
+
+    #[test]
+    fn test_fault_guard_can_be_reused_sequentially() {
+        for i in 0..5 {
+            assert_eq!(with_fault_guard(|| i), i);
+        }
+    }
+}
@@ -1,8 +1,21 @@
 //! Storage layer implementation
 
+pub mod alloc;
+pub mod cache;
 pub mod checksum;
+pub mod checksum_algorithm;
+pub mod codec;
+pub mod format;
+pub mod header;
+pub mod mmap_guard;
 pub mod page;
 pub mod page_constants;
 pub mod page_header;
 pub mod page_io;
+pub mod page_map;
 pub mod page_type;
+pub mod pool;
+pub mod read_ahead;
+pub mod repair;
+pub mod swap;
+pub mod txn;
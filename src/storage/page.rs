@@ -4,12 +4,32 @@ use crate::common::error::Error;
 use crate::storage::page_constants::{PAGE_HEADER_SIZE, PAGE_SIZE, PAGE_USABLE_SIZE};
 use crate::storage::page_header::PageHeader;
 
-/// Page - 4KB aligned byte array with typed header access
-#[repr(C, align(4096))]
-pub struct Page {
-    buffer: [u8; PAGE_SIZE],
+// `#[repr(align(N))]` requires a literal, but PAGE_SIZE is a cfg-selected
+// const, so the struct definition itself must be generated per page-size
+// feature rather than written once against the const.
+macro_rules! define_page {
+    ($align:literal) => {
+        /// Page - `PAGE_SIZE`-aligned byte array with typed header access
+        #[repr(C, align($align))]
+        pub struct Page {
+            buffer: [u8; PAGE_SIZE],
+        }
+    };
 }
 
+#[cfg(not(any(
+    feature = "page-size-8k",
+    feature = "page-size-16k",
+    feature = "page-size-64k"
+)))]
+define_page!(4096);
+#[cfg(feature = "page-size-8k")]
+define_page!(8192);
+#[cfg(feature = "page-size-16k")]
+define_page!(16384);
+#[cfg(feature = "page-size-64k")]
+define_page!(65536);
+
 impl Page {
     /// Create a new zero-initialized page
     pub fn new() -> Self {
@@ -18,6 +38,8 @@ impl Page {
         };
         // Initialize header to default
         *page.header_mut() = PageHeader::default();
+        page.header_mut()
+            .set_checksum_algorithm(crate::storage::checksum_algorithm::default_checksum_algorithm());
         page
     }
 
@@ -75,29 +97,70 @@ impl Page {
     ///
     /// # Errors
     ///
-    /// Returns an error if the page size is invalid (should never happen with Page struct)
+    /// This never fails in practice (the buffer is always exactly
+    /// `PAGE_SIZE` bytes); it returns `Result` to leave room for a future
+    /// fallible codec path.
     pub fn calculate_checksum(&mut self) -> Result<(), Error> {
-        let checksum = crate::storage::checksum::calculate_page_checksum(&self.buffer)?;
+        let checksum = PageHeader::compute_checksum(&self.buffer);
         self.header_mut().checksum = checksum;
         Ok(())
     }
 
     /// Verify page checksum
     pub fn verify_checksum(&self) -> bool {
-        match crate::storage::checksum::calculate_page_checksum(&self.buffer) {
-            Ok(calculated) => {
-                // Copy checksum value to avoid unaligned access
-                let stored_checksum = self.header().checksum;
-                calculated == stored_checksum
-            }
-            Err(_) => false,
-        }
+        self.header().verify(&self.buffer).is_ok()
     }
 
     /// Check if page is corrupted
     pub fn is_corrupted(&self) -> bool {
         !self.verify_checksum()
     }
+
+    /// Write a logical body through a codec pipeline, storing the result
+    /// (compressed and/or encrypted) in the page's data area and flagging
+    /// the header accordingly.
+    ///
+    /// `free_space` is set to reflect the logical (decompressed) free
+    /// space, not the physical on-disk size written by the pipeline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the encoded body doesn't fit in the page.
+    pub fn write_encoded_body(
+        &mut self,
+        logical: &[u8],
+        pipeline: &crate::storage::codec::CodecPipeline,
+    ) -> Result<(), Error> {
+        let encoded = pipeline.encode_into_page_body(logical)?;
+        self.data_mut().copy_from_slice(&encoded);
+
+        let header = self.header_mut();
+        header.set_compressed(pipeline.is_compressed());
+        header.set_encrypted(pipeline.is_encrypted());
+        #[allow(clippy::cast_possible_truncation)]
+        let free_space = PAGE_USABLE_SIZE.saturating_sub(logical.len()) as u16;
+        header.free_space = free_space;
+
+        Ok(())
+    }
+
+    /// Recover the logical body previously written with
+    /// [`Page::write_encoded_body`] using a matching codec pipeline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stored body is not valid output of the
+    /// pipeline (e.g. wrong key, or corrupted data).
+    pub fn read_encoded_body(
+        &self,
+        pipeline: &crate::storage::codec::CodecPipeline,
+    ) -> Result<Vec<u8>, Error> {
+        let stored: [u8; PAGE_USABLE_SIZE] = self
+            .data()
+            .try_into()
+            .expect("page data area is always PAGE_USABLE_SIZE bytes");
+        pipeline.decode_from_page_body(&stored)
+    }
 }
 
 impl Default for Page {
@@ -113,8 +176,8 @@ mod tests {
 
     #[test]
     fn test_page_alignment() {
-        // Verify the Page struct is properly aligned
-        assert_eq!(std::mem::align_of::<Page>(), 4096);
+        // The Page struct is always aligned to its own PAGE_SIZE
+        assert_eq!(std::mem::align_of::<Page>(), PAGE_SIZE);
     }
 
     #[test]
@@ -141,4 +204,21 @@ mod tests {
         let raw_page_type = page.buffer[0];
         assert_eq!(raw_page_type, PageType::Leaf as u8);
     }
+
+    #[test]
+    fn test_page_encoded_body_roundtrip() {
+        use crate::storage::codec::CodecPipeline;
+
+        let mut page = Page::new();
+        let pipeline = CodecPipeline::compressed_and_encrypted([0x7a; 32]);
+        let logical = vec![b'l'; 500];
+
+        page.write_encoded_body(&logical, &pipeline).unwrap();
+        assert!(page.header().is_compressed());
+        assert!(page.header().is_encrypted());
+        assert_eq!(page.header().free_space as usize, PAGE_USABLE_SIZE - 500);
+
+        let recovered = page.read_encoded_body(&pipeline).unwrap();
+        assert_eq!(recovered, logical);
+    }
 }
@@ -1,46 +1,119 @@
 //! Page constants and fundamental types for the storage layer
+//!
+//! The page size and `PageId` width are both compile-time configurable via
+//! cargo features, trading page-table-style width for addressable space
+//! much like the RISC-V sv32/sv39/sv48/sv57 addressing modes do:
+//!
+//! - `page-addr64` widens [`PageId`] from `u32` to `u64`.
+//! - `page-size-8k` / `page-size-16k` / `page-size-64k` select a larger
+//!   [`PAGE_SIZE`]; the default with none of them enabled is 4 KiB.
+//!
+//! Exactly one page-size feature may be enabled at a time. The chosen mode
+//! is recorded by [`crate::storage::format::StorageFormat`] and validated
+//! against the on-disk header so a file created under one mode is rejected
+//! when opened under another.
 
-/// Page size in bytes - must be power of 2 and >= 4KB
+#[cfg(all(feature = "page-size-8k", feature = "page-size-16k"))]
+compile_error!("only one page-size-* feature may be enabled at a time");
+#[cfg(all(feature = "page-size-8k", feature = "page-size-64k"))]
+compile_error!("only one page-size-* feature may be enabled at a time");
+#[cfg(all(feature = "page-size-16k", feature = "page-size-64k"))]
+compile_error!("only one page-size-* feature may be enabled at a time");
+
+/// Page size in bytes - must be a power of 2 and >= 4KB
+#[cfg(feature = "page-size-8k")]
+pub const PAGE_SIZE: usize = 8192;
+/// Page size in bytes - must be a power of 2 and >= 4KB
+#[cfg(feature = "page-size-16k")]
+pub const PAGE_SIZE: usize = 16384;
+/// Page size in bytes - must be a power of 2 and >= 4KB
+#[cfg(feature = "page-size-64k")]
+pub const PAGE_SIZE: usize = 65536;
+/// Page size in bytes - must be a power of 2 and >= 4KB
+#[cfg(not(any(
+    feature = "page-size-8k",
+    feature = "page-size-16k",
+    feature = "page-size-64k"
+)))]
 pub const PAGE_SIZE: usize = 4096;
 
 /// Page header size in bytes - MUST match plan/storage-format.md
-pub const PAGE_HEADER_SIZE: usize = 16;
+///
+/// 20 bytes in the default 32-bit addressing mode: the `lsn` field widened
+/// from `u32` to `u64` to carry MVCC version stamps (see
+/// [`crate::storage::txn`]), growing the header from its original 16
+/// bytes. Under `page-addr64`, `page_id` also widens to `u64`, adding
+/// another 4 bytes.
+#[cfg(not(feature = "page-addr64"))]
+pub const PAGE_HEADER_SIZE: usize = 20;
+/// Page header size in bytes - MUST match plan/storage-format.md
+#[cfg(feature = "page-addr64")]
+pub const PAGE_HEADER_SIZE: usize = 24;
 
 /// Usable space in page after header
 pub const PAGE_USABLE_SIZE: usize = PAGE_SIZE - PAGE_HEADER_SIZE;
 
-/// Page ID type - supports 16TB databases (4KB * 2^32)
+/// Page ID type - `u32` by default (supports ~16TB databases at 4KB pages),
+/// or `u64` with the `page-addr64` feature for databases that outgrow that.
+#[cfg(not(feature = "page-addr64"))]
 pub type PageId = u32;
+/// Page ID type, widened for the `page-addr64` addressing mode.
+#[cfg(feature = "page-addr64")]
+pub type PageId = u64;
 
 /// Invalid page ID sentinel value
 pub const INVALID_PAGE_ID: PageId = 0;
 
 /// Maximum valid page ID
+#[cfg(not(feature = "page-addr64"))]
 pub const MAX_PAGE_ID: PageId = u32::MAX;
+/// Maximum valid page ID
+#[cfg(feature = "page-addr64")]
+pub const MAX_PAGE_ID: PageId = u64::MAX;
+
+/// Number of bits used to represent a `PageId`, as recorded by
+/// [`crate::storage::format::StorageFormat`].
+#[cfg(not(feature = "page-addr64"))]
+pub const PAGE_ID_BITS: u8 = 32;
+/// Number of bits used to represent a `PageId`.
+#[cfg(feature = "page-addr64")]
+pub const PAGE_ID_BITS: u8 = 64;
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
+    #[cfg(not(feature = "page-addr64"))]
     fn test_page_constants() {
+        assert_eq!(PAGE_HEADER_SIZE, 20);
+    }
+
+    #[test]
+    #[cfg(feature = "page-addr64")]
+    fn test_page_constants_addr64() {
+        assert_eq!(PAGE_HEADER_SIZE, 24);
+    }
+
+    #[test]
+    #[cfg(not(any(
+        feature = "page-size-8k",
+        feature = "page-size-16k",
+        feature = "page-size-64k"
+    )))]
+    fn test_default_page_size() {
         assert_eq!(PAGE_SIZE, 4096);
-        assert_eq!(PAGE_HEADER_SIZE, 16);
-        assert_eq!(PAGE_USABLE_SIZE, 4080);
     }
 
     #[test]
     fn test_page_id_constants() {
         assert_eq!(INVALID_PAGE_ID, 0);
-        assert_eq!(MAX_PAGE_ID, u32::MAX);
+        assert_eq!(MAX_PAGE_ID, PageId::MAX);
     }
 
     #[test]
     fn test_page_size_is_power_of_two() {
         assert!(PAGE_SIZE.is_power_of_two());
-        // PAGE_SIZE is a constant 4096, so this would always be true
-        // Just verify the actual value instead
-        assert_eq!(PAGE_SIZE, 4096);
     }
 
     #[test]
@@ -50,6 +123,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "page-addr64"))]
     fn test_database_size_calculation() {
         // With 32-bit page IDs and 4KB pages, we can address:
         // u32::MAX * 4KB = 17592186040320 bytes (approximately 16TB)
@@ -1,27 +1,47 @@
-//! Page header structure - exactly 16 bytes at the beginning of each page
-//! MUST match plan/storage-format.md specification
+//! Page header structure - the fixed-layout block at the beginning of
+//! every page. MUST match plan/storage-format.md specification
+//!
+//! The header grew from 16 to 20 bytes when `lsn` widened from `u32` to
+//! `u64` to support the MVCC copy-on-write versioning in
+//! [`crate::storage::txn`]: every other field keeps its original offset,
+//! only the trailing `lsn` field and the total size changed. Under the
+//! `page-addr64` feature (see [`crate::storage::page_constants`]),
+//! `page_id` widens to `u64` as well, which shifts every field after it
+//! and grows the header to 24 bytes.
 
+use crate::common::error::Error;
+use crate::storage::checksum_algorithm::ChecksumAlgorithm;
 use crate::storage::page_constants::{PageId, INVALID_PAGE_ID, PAGE_USABLE_SIZE};
 use crate::storage::page_type::PageType;
 use bytemuck::{Pod, Zeroable};
 
-/// Page header - exactly 16 bytes as specified in plan/storage-format.md
+/// Byte offset of the `checksum` field within [`PageHeader`], used to zero
+/// that region out before hashing a page so verification is stable.
+const CHECKSUM_OFFSET: usize = std::mem::offset_of!(PageHeader, checksum);
+const CHECKSUM_SIZE: usize = std::mem::size_of::<u32>();
+/// Byte offset of the `flags` field, read directly out of raw page bytes
+/// to select a [`ChecksumAlgorithm`] without needing a parsed header.
+const FLAGS_OFFSET: usize = std::mem::offset_of!(PageHeader, flags);
+
+/// Page header - exactly `PAGE_HEADER_SIZE` bytes as specified in
+/// plan/storage-format.md
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(C, packed(1))]
 pub struct PageHeader {
-    /// Page number (4 bytes)
-    pub page_id: u32,
+    /// Page number (4 bytes, or 8 under `page-addr64`)
+    pub page_id: PageId,
     /// Page type enum (1 byte)
     pub page_type: PageType,
     /// Page-specific flags (1 byte)
     pub flags: u8,
     /// Bytes of free space (2 bytes)
     pub free_space: u16,
-    /// CRC32 of page content (4 bytes)
+    /// Checksum of page content, computed with the algorithm recorded in
+    /// `flags` (4 bytes)
     pub checksum: u32,
-    /// Log sequence number (4 bytes)
-    pub lsn: u32,
-    // Total: 16 bytes (exactly as specified)
+    /// Log sequence number / MVCC version stamp (8 bytes)
+    pub lsn: u64,
+    // Total: PAGE_HEADER_SIZE bytes (exactly as specified)
 }
 
 // SAFETY: PageHeader is a POD type with no padding or invalid values
@@ -36,7 +56,7 @@ impl Default for PageHeader {
             page_id: INVALID_PAGE_ID,
             page_type: PageType::Header, // Default to Header type
             flags: 0,
-            free_space: PAGE_USABLE_SIZE as u16, // PAGE_USABLE_SIZE is 4080, fits in u16
+            free_space: PAGE_USABLE_SIZE as u16, // PAGE_USABLE_SIZE fits in u16
             checksum: 0,
             lsn: 0,
         }
@@ -80,6 +100,109 @@ impl PageHeader {
             self.flags &= !0x02;
         }
     }
+
+    /// Check if the page body is stored compressed on disk
+    pub fn is_compressed(&self) -> bool {
+        self.flags & 0x04 != 0
+    }
+
+    /// Set the compressed flag
+    pub fn set_compressed(&mut self, compressed: bool) {
+        if compressed {
+            self.flags |= 0x04;
+        } else {
+            self.flags &= !0x04;
+        }
+    }
+
+    /// Check if the page body is stored encrypted on disk
+    pub fn is_encrypted(&self) -> bool {
+        self.flags & 0x08 != 0
+    }
+
+    /// Set the encrypted flag
+    pub fn set_encrypted(&mut self, encrypted: bool) {
+        if encrypted {
+            self.flags |= 0x08;
+        } else {
+            self.flags &= !0x08;
+        }
+    }
+
+    /// Check if this physical page is still referenced by a live
+    /// [`crate::storage::page_map::PageMapSnapshot`], meaning a write to
+    /// its logical page must copy-on-write to a fresh physical slot
+    /// instead of mutating it in place.
+    pub fn is_cow_shared(&self) -> bool {
+        self.flags & 0x10 != 0
+    }
+
+    /// Set the copy-on-write-shared flag.
+    pub fn set_cow_shared(&mut self, shared: bool) {
+        if shared {
+            self.flags |= 0x10;
+        } else {
+            self.flags &= !0x10;
+        }
+    }
+
+    /// Check which [`ChecksumAlgorithm`] this header is set to use.
+    #[must_use]
+    pub fn checksum_algorithm(&self) -> ChecksumAlgorithm {
+        ChecksumAlgorithm::from_flags_byte(self.flags)
+    }
+
+    /// Select the [`ChecksumAlgorithm`] `calculate_checksum`/`verify`
+    /// should use for this page, leaving the other `flags` bits as they
+    /// are.
+    pub fn set_checksum_algorithm(&mut self, algorithm: ChecksumAlgorithm) {
+        self.flags = algorithm.encode_into_flags(self.flags);
+    }
+
+    /// Compute the checksum of a full page's bytes (header and body
+    /// together) using the algorithm recorded in its own `flags` byte,
+    /// treating the `checksum` field's own bytes as zero so the result
+    /// doesn't depend on whatever value is currently stored there.
+    #[must_use]
+    pub fn compute_checksum(page_bytes: &[u8]) -> u32 {
+        let algorithm = ChecksumAlgorithm::from_flags_byte(page_bytes[FLAGS_OFFSET]);
+        Self::compute_checksum_with(algorithm, page_bytes)
+    }
+
+    /// Like [`PageHeader::compute_checksum`], but with an explicit
+    /// algorithm instead of reading one out of `page_bytes`.
+    #[must_use]
+    pub fn compute_checksum_with(algorithm: ChecksumAlgorithm, page_bytes: &[u8]) -> u32 {
+        let mut scratch = page_bytes.to_vec();
+        scratch[CHECKSUM_OFFSET..CHECKSUM_OFFSET + CHECKSUM_SIZE].fill(0);
+        algorithm.compute(&scratch)
+    }
+
+    /// Recompute the checksum over `page_bytes` and store it in this
+    /// header.
+    pub fn update_checksum(&mut self, page_bytes: &[u8]) {
+        self.checksum = Self::compute_checksum(page_bytes);
+    }
+
+    /// Verify this header's stored checksum against `page_bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ChecksumMismatch`] if the checksum computed from
+    /// `page_bytes` doesn't match the one stored in this header.
+    pub fn verify(&self, page_bytes: &[u8]) -> Result<(), Error> {
+        let expected = self.checksum;
+        let found = Self::compute_checksum(page_bytes);
+        if expected == found {
+            Ok(())
+        } else {
+            Err(Error::checksum_mismatch(
+                u64::from(self.page_id),
+                expected,
+                found,
+            ))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -88,6 +211,7 @@ mod tests {
     use bytemuck::{bytes_of, from_bytes};
 
     #[test]
+    #[cfg(not(feature = "page-addr64"))]
     fn test_field_offsets() {
         use std::mem::offset_of;
 
@@ -98,13 +222,31 @@ mod tests {
         assert_eq!(offset_of!(PageHeader, free_space), 6);
         assert_eq!(offset_of!(PageHeader, checksum), 8);
         assert_eq!(offset_of!(PageHeader, lsn), 12);
-        assert_eq!(std::mem::size_of::<PageHeader>(), 16);
+        assert_eq!(std::mem::size_of::<PageHeader>(), 20);
+    }
+
+    #[test]
+    #[cfg(feature = "page-addr64")]
+    fn test_field_offsets_addr64() {
+        use std::mem::offset_of;
+
+        // Widening page_id to u64 shifts every later field.
+        assert_eq!(offset_of!(PageHeader, page_id), 0);
+        assert_eq!(offset_of!(PageHeader, page_type), 8);
+        assert_eq!(offset_of!(PageHeader, flags), 9);
+        assert_eq!(offset_of!(PageHeader, free_space), 10);
+        assert_eq!(offset_of!(PageHeader, checksum), 12);
+        assert_eq!(offset_of!(PageHeader, lsn), 16);
+        assert_eq!(std::mem::size_of::<PageHeader>(), 24);
     }
 
     #[test]
     fn test_page_header_size() {
-        // Ensure the header is exactly 16 bytes as per plan/storage-format.md
-        assert_eq!(std::mem::size_of::<PageHeader>(), 16);
+        // Ensure the header matches PAGE_HEADER_SIZE as per plan/storage-format.md
+        assert_eq!(
+            std::mem::size_of::<PageHeader>(),
+            crate::storage::page_constants::PAGE_HEADER_SIZE
+        );
     }
 
     #[test]
@@ -112,7 +254,10 @@ mod tests {
         // Test that we can safely cast to/from bytes
         let header = PageHeader::new(PageType::BTreeLeaf, 42);
         let bytes = bytes_of(&header);
-        assert_eq!(bytes.len(), 16);
+        assert_eq!(
+            bytes.len(),
+            crate::storage::page_constants::PAGE_HEADER_SIZE
+        );
 
         let header2: &PageHeader = from_bytes(bytes);
         assert_eq!(header, *header2);
@@ -138,4 +283,128 @@ mod tests {
         assert!(!header.is_dirty());
         assert!(header.is_pinned());
     }
+
+    #[test]
+    fn test_page_header_codec_flags() {
+        let mut header = PageHeader::default();
+
+        assert!(!header.is_compressed());
+        header.set_compressed(true);
+        assert!(header.is_compressed());
+
+        assert!(!header.is_encrypted());
+        header.set_encrypted(true);
+        assert!(header.is_encrypted());
+
+        // Codec flags are independent from the dirty/pinned bits
+        header.set_dirty(true);
+        header.set_pinned(true);
+        assert!(header.is_compressed());
+        assert!(header.is_encrypted());
+        assert!(header.is_dirty());
+        assert!(header.is_pinned());
+
+        header.set_compressed(false);
+        assert!(!header.is_compressed());
+        assert!(header.is_encrypted());
+    }
+
+    #[test]
+    fn test_page_header_cow_shared_flag() {
+        let mut header = PageHeader::default();
+
+        assert!(!header.is_cow_shared());
+        header.set_cow_shared(true);
+        assert!(header.is_cow_shared());
+
+        // Independent from the other flag bits.
+        header.set_dirty(true);
+        assert!(header.is_cow_shared());
+        assert!(header.is_dirty());
+
+        header.set_cow_shared(false);
+        assert!(!header.is_cow_shared());
+        assert!(header.is_dirty());
+    }
+
+    #[test]
+    fn test_checksum_roundtrip() {
+        let mut header = PageHeader::new(PageType::BTreeLeaf, 7);
+        let mut page_bytes = vec![0xABu8; 256];
+
+        header.update_checksum(&page_bytes);
+        assert!(header.verify(&page_bytes).is_ok());
+
+        page_bytes[100] ^= 0x01;
+        assert!(header.verify(&page_bytes).is_err());
+    }
+
+    #[test]
+    fn test_checksum_ignores_stale_checksum_field_bytes() {
+        // Two page buffers identical except for the checksum field itself
+        // must still hash the same, since compute_checksum treats that
+        // region as zero.
+        let mut page_a = vec![0x5Au8; 256];
+        let mut page_b = vec![0x5Au8; 256];
+        page_a[CHECKSUM_OFFSET..CHECKSUM_OFFSET + CHECKSUM_SIZE].copy_from_slice(&[0; 4]);
+        page_b[CHECKSUM_OFFSET..CHECKSUM_OFFSET + CHECKSUM_SIZE].copy_from_slice(&[0xFF; 4]);
+
+        assert_eq!(
+            PageHeader::compute_checksum(&page_a),
+            PageHeader::compute_checksum(&page_b)
+        );
+    }
+
+    #[test]
+    fn test_verify_reports_page_id_and_values() {
+        let mut header = PageHeader::new(PageType::BTreeLeaf, 99);
+        let page_bytes = vec![0u8; 256];
+        header.update_checksum(&page_bytes);
+        header.checksum ^= 0xFFFF_FFFF;
+
+        match header.verify(&page_bytes) {
+            Err(Error::ChecksumMismatch {
+                page_id, expected, ..
+            }) => {
+                assert_eq!(page_id, 99);
+                assert_eq!(expected, header.checksum);
+            }
+            other => panic!("expected ChecksumMismatch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_default_header_uses_crc32c() {
+        let header = PageHeader::default();
+        assert_eq!(header.checksum_algorithm(), ChecksumAlgorithm::Crc32c);
+    }
+
+    #[test]
+    fn test_set_checksum_algorithm_roundtrips_through_flags() {
+        let mut header = PageHeader::new(PageType::BTreeLeaf, 1);
+        header.set_dirty(true);
+
+        header.set_checksum_algorithm(ChecksumAlgorithm::XxHash64);
+        assert_eq!(header.checksum_algorithm(), ChecksumAlgorithm::XxHash64);
+        // Changing the checksum algorithm must not disturb unrelated bits.
+        assert!(header.is_dirty());
+    }
+
+    #[test]
+    fn test_verify_uses_the_algorithm_stamped_in_page_bytes() {
+        let mut header = PageHeader::new(PageType::BTreeLeaf, 1);
+        header.set_checksum_algorithm(ChecksumAlgorithm::Crc32);
+
+        // Build a page buffer whose flags byte matches the header so
+        // `compute_checksum` dispatches to the same algorithm.
+        let mut page_bytes = vec![0u8; 256];
+        page_bytes[FLAGS_OFFSET] = header.flags;
+
+        header.update_checksum(&page_bytes);
+        assert_eq!(
+            header.checksum,
+            PageHeader::compute_checksum_with(ChecksumAlgorithm::Crc32, &page_bytes)
+        );
+        assert!(header.verify(&page_bytes).is_ok());
+    }
 }
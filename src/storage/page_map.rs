@@ -0,0 +1,574 @@
+//! Logical-to-physical page mapping with copy-on-write snapshots
+//!
+//! [`crate::storage::alloc::PageAllocator`] hands out physical page ids,
+//! and every other part of the storage layer has so far addressed pages
+//! by that physical id directly. [`PageMap`] adds a level of indirection:
+//! callers address logical page ids, which `PageMap` resolves to whatever
+//! physical page currently backs them.
+//!
+//! That indirection is what makes [`PageMap::snapshot`] possible: it
+//! freezes the current logical-to-physical mapping without copying
+//! anything. A write to a logical page whose physical copy some live
+//! snapshot still observes (it was written at or before the newest live
+//! snapshot's generation) transparently allocates a fresh physical page
+//! (via [`crate::storage::alloc::PageAllocator`]) instead of mutating the
+//! shared one in place, stamping the new copy's `lsn` with the current
+//! write generation. The original physical page is left untouched, and
+//! [`PageMap::read_at`] lets a caller holding an older [`PageMapSnapshot`]
+//! resolve back to it instead of the logical page's current mapping.
+//!
+//! This is a different layer from [`crate::storage::txn`]'s MVCC version
+//! map: `txn` keeps every version of a page in memory, keyed by LSN, for
+//! a single open database session. `PageMap` instead keeps exactly one
+//! physical copy "live" per logical page at a time (plus one retained copy
+//! per page a live snapshot still observes) and is meant to be persisted:
+//! [`PageMap::flush`] serializes the whole mapping into a fresh chain of
+//! `PageMap` pages (mirroring the `FreeList` chain in
+//! [`crate::storage::alloc`]) and returns its head id, and [`PageMap::load`]
+//! rebuilds a `PageMap` from that chain. Because a snapshot's usefulness
+//! ends with the process that took it, snapshot/generation bookkeeping
+//! itself is not persisted, only the mapping is.
+
+use crate::common::error::Error;
+use crate::storage::alloc::PageAllocator;
+use crate::storage::page::Page;
+use crate::storage::page_constants::{PageId, INVALID_PAGE_ID, PAGE_USABLE_SIZE};
+use crate::storage::page_io::{read_page_from_file, write_page_to_file};
+use crate::storage::page_type::PageType;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::rc::Rc;
+
+const PAGE_ID_SIZE: usize = std::mem::size_of::<PageId>();
+const COUNT_SIZE: usize = std::mem::size_of::<u32>();
+const CHAIN_BODY_HEADER_SIZE: usize = PAGE_ID_SIZE + COUNT_SIZE;
+const ENTRY_SIZE: usize = PAGE_ID_SIZE * 2;
+
+/// How many `(logical, physical)` entries fit in one `PageMap` chain page.
+pub const MAP_ENTRIES_PER_PAGE: usize = (PAGE_USABLE_SIZE - CHAIN_BODY_HEADER_SIZE) / ENTRY_SIZE;
+
+/// Tracks which snapshot generations are still alive, so [`PageMap`] can
+/// tell whether a logical page's current physical copy must be preserved
+/// rather than overwritten in place.
+#[derive(Default)]
+struct SnapshotRegistry {
+    live: BTreeMap<u64, usize>,
+}
+
+impl SnapshotRegistry {
+    fn register(&mut self, generation: u64) {
+        *self.live.entry(generation).or_insert(0) += 1;
+    }
+
+    fn unregister(&mut self, generation: u64) {
+        if let std::collections::btree_map::Entry::Occupied(mut entry) =
+            self.live.entry(generation)
+        {
+            *entry.get_mut() -= 1;
+            if *entry.get() == 0 {
+                entry.remove();
+            }
+        }
+    }
+
+    /// The newest generation any live snapshot was taken at, or `None` if
+    /// there are no live snapshots. A physical page is still shared with
+    /// a live snapshot exactly when this is `>=` the generation it was
+    /// last written in.
+    fn max_live(&self) -> Option<u64> {
+        self.live.keys().next_back().copied()
+    }
+}
+
+/// Maps logical page ids to physical page ids, with copy-on-write
+/// snapshots. See the module docs for how this differs from
+/// [`crate::storage::txn`]'s in-memory MVCC version map.
+pub struct PageMap {
+    mapping: HashMap<PageId, PageId>,
+    written_at: HashMap<PageId, u64>,
+    /// Every physical page a logical id has ever mapped to, as
+    /// `(generation, physical_id)` pairs in ascending generation order.
+    /// [`PageMap::read_at`] resolves a snapshot's generation against this
+    /// instead of `mapping`, which only ever holds the current one.
+    history: HashMap<PageId, Vec<(u64, PageId)>>,
+    current_generation: u64,
+    snapshots: Rc<RefCell<SnapshotRegistry>>,
+}
+
+impl PageMap {
+    /// Create an empty page map with no logical pages mapped yet.
+    pub fn new() -> Self {
+        Self {
+            mapping: HashMap::new(),
+            written_at: HashMap::new(),
+            history: HashMap::new(),
+            current_generation: 0,
+            snapshots: Rc::new(RefCell::new(SnapshotRegistry::default())),
+        }
+    }
+
+    /// Rebuild a page map from a chain of `PageMap` pages previously
+    /// written by [`PageMap::flush`], starting at `head`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a chain page can't be read.
+    pub fn load(file: &mut File, head: PageId) -> Result<Self, Error> {
+        let mut mapping = HashMap::new();
+        let mut current = head;
+        while current != INVALID_PAGE_ID {
+            let page = read_page_from_file(file, u64::from(current))?;
+            mapping.extend(read_entries(&page));
+            current = read_next(&page);
+        }
+        Ok(Self {
+            mapping,
+            written_at: HashMap::new(),
+            history: HashMap::new(),
+            current_generation: 0,
+            snapshots: Rc::new(RefCell::new(SnapshotRegistry::default())),
+        })
+    }
+
+    /// Persist the current mapping as a fresh chain of `PageMap` pages,
+    /// returning the new head id (e.g. to be stamped into the database
+    /// header page alongside the free-list head).
+    ///
+    /// # Errors
+    ///
+    /// Propagates any error from allocating or writing a chain page.
+    pub fn flush(&self, file: &mut File, allocator: &mut PageAllocator) -> Result<PageId, Error> {
+        let entries: Vec<(PageId, PageId)> =
+            self.mapping.iter().map(|(&logical, &physical)| (logical, physical)).collect();
+
+        let mut head = INVALID_PAGE_ID;
+        for chunk in entries.chunks(MAP_ENTRIES_PER_PAGE) {
+            let page_id = allocator.alloc_page(file, PageType::PageMap)?;
+            let mut page = read_page_from_file(file, u64::from(page_id))?;
+            write_next(&mut page, head);
+            write_entries(&mut page, chunk);
+            page.calculate_checksum()?;
+            write_page_to_file(file, u64::from(page_id), &page)?;
+            head = page_id;
+        }
+        Ok(head)
+    }
+
+    /// Map a brand-new logical page to a freshly allocated physical page
+    /// of `page_type`, returning the physical id.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any error from allocating the physical page.
+    pub fn create_mapping(
+        &mut self,
+        file: &mut File,
+        allocator: &mut PageAllocator,
+        logical_id: PageId,
+        page_type: PageType,
+    ) -> Result<PageId, Error> {
+        let physical_id = allocator.alloc_page(file, page_type)?;
+        self.mapping.insert(logical_id, physical_id);
+        self.written_at.insert(logical_id, self.current_generation);
+        self.history
+            .entry(logical_id)
+            .or_default()
+            .push((self.current_generation, physical_id));
+        Ok(physical_id)
+    }
+
+    /// Resolve a logical page id to its current physical page id.
+    pub fn translate(&self, logical_id: PageId) -> Option<PageId> {
+        self.mapping.get(&logical_id).copied()
+    }
+
+    /// Read the current version of a logical page.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotFound`] if `logical_id` isn't mapped, and
+    /// propagates I/O and checksum errors from the underlying read.
+    pub fn read_page(&self, file: &mut File, logical_id: PageId) -> Result<Page, Error> {
+        let physical_id = self.physical_id(logical_id)?;
+        read_page_from_file(file, u64::from(physical_id))
+    }
+
+    /// Read a logical page as of a previously taken snapshot, rather than
+    /// its current version.
+    ///
+    /// This is what actually delivers the point-in-time reads
+    /// [`PageMap::snapshot`] exists for: [`PageMap::write_page`] only
+    /// *preserves* a physical page a live snapshot still observes, it
+    /// doesn't by itself give a caller any way back to it. `read_at`
+    /// resolves `logical_id` against the mapping as it stood at
+    /// `snapshot.generation()`, using the per-logical-id write history
+    /// kept alongside the live `mapping`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotFound`] if `logical_id` wasn't mapped as of
+    /// `snapshot`'s generation (including if it didn't exist yet), and
+    /// propagates I/O and checksum errors from the underlying read.
+    pub fn read_at(
+        &self,
+        file: &mut File,
+        snapshot: &PageMapSnapshot,
+        logical_id: PageId,
+    ) -> Result<Page, Error> {
+        let physical_id = self.physical_id_at(snapshot.generation(), logical_id)?;
+        read_page_from_file(file, u64::from(physical_id))
+    }
+
+    /// Resolve `logical_id` to the physical page id it mapped to at
+    /// `generation`, by walking its write history backwards to the most
+    /// recent entry at or before `generation`.
+    fn physical_id_at(&self, generation: u64, logical_id: PageId) -> Result<PageId, Error> {
+        self.history
+            .get(&logical_id)
+            .and_then(|entries| {
+                entries
+                    .iter()
+                    .rev()
+                    .find(|&&(written_at, _)| written_at <= generation)
+            })
+            .map(|&(_, physical_id)| physical_id)
+            .ok_or_else(|| {
+                Error::not_found(format!(
+                    "logical page {logical_id} has no physical mapping as of generation {generation}"
+                ))
+            })
+    }
+
+    /// Write a new version of a logical page.
+    ///
+    /// If the page's current physical copy was written at or before the
+    /// newest live snapshot's generation (so some live snapshot still
+    /// observes it), it is left untouched and the write instead lands on a
+    /// freshly allocated physical page, which becomes `logical_id`'s new
+    /// mapping. Otherwise the existing physical page is overwritten in
+    /// place.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotFound`] if `logical_id` isn't mapped (use
+    /// [`PageMap::create_mapping`] first), and propagates I/O errors from
+    /// allocating or writing the physical page.
+    pub fn write_page(
+        &mut self,
+        file: &mut File,
+        allocator: &mut PageAllocator,
+        logical_id: PageId,
+        mut page: Page,
+    ) -> Result<(), Error> {
+        let physical_id = self.physical_id(logical_id)?;
+        let written_at = self.written_at.get(&logical_id).copied().unwrap_or(0);
+        let shared = self
+            .snapshots
+            .borrow()
+            .max_live()
+            .is_some_and(|max| max >= written_at);
+
+        let target_physical = if shared {
+            allocator.alloc_page(file, page.header().page_type)?
+        } else {
+            physical_id
+        };
+
+        page.header_mut().page_id = target_physical;
+        page.header_mut().lsn = self.current_generation;
+        page.header_mut().set_cow_shared(false);
+        page.calculate_checksum()?;
+        write_page_to_file(file, u64::from(target_physical), &page)?;
+
+        self.mapping.insert(logical_id, target_physical);
+        self.written_at.insert(logical_id, self.current_generation);
+        self.history
+            .entry(logical_id)
+            .or_default()
+            .push((self.current_generation, target_physical));
+        Ok(())
+    }
+
+    /// Freeze the current mapping as a snapshot: until the returned
+    /// [`PageMapSnapshot`] (and every other snapshot taken before it, if
+    /// any are still alive) is dropped, a write to any logical page whose
+    /// physical copy already exists will copy-on-write to a new physical
+    /// page rather than mutate the existing one in place.
+    pub fn snapshot(&mut self) -> PageMapSnapshot {
+        let generation = self.current_generation;
+        self.snapshots.borrow_mut().register(generation);
+        self.current_generation += 1;
+        PageMapSnapshot {
+            registry: Rc::clone(&self.snapshots),
+            generation,
+        }
+    }
+
+    fn physical_id(&self, logical_id: PageId) -> Result<PageId, Error> {
+        self.translate(logical_id).ok_or_else(|| {
+            Error::not_found(format!("no physical page mapped for logical page {logical_id}"))
+        })
+    }
+}
+
+impl Default for PageMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A frozen view of a [`PageMap`]'s mapping at the moment it was taken.
+/// See [`PageMap::snapshot`].
+pub struct PageMapSnapshot {
+    registry: Rc<RefCell<SnapshotRegistry>>,
+    generation: u64,
+}
+
+impl PageMapSnapshot {
+    /// The write generation this snapshot pinned: every logical page's
+    /// physical copy as of this generation or earlier is preserved for
+    /// as long as this snapshot is alive.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+impl Drop for PageMapSnapshot {
+    fn drop(&mut self) {
+        self.registry.borrow_mut().unregister(self.generation);
+    }
+}
+
+fn read_next(page: &Page) -> PageId {
+    let bytes: [u8; PAGE_ID_SIZE] = page.data()[..PAGE_ID_SIZE]
+        .try_into()
+        .expect("slice has exactly PAGE_ID_SIZE bytes");
+    PageId::from_le_bytes(bytes)
+}
+
+fn write_next(page: &mut Page, next: PageId) {
+    page.data_mut()[..PAGE_ID_SIZE].copy_from_slice(&next.to_le_bytes());
+}
+
+fn read_entries(page: &Page) -> Vec<(PageId, PageId)> {
+    let body = page.data();
+    let count = u32::from_le_bytes(
+        body[PAGE_ID_SIZE..CHAIN_BODY_HEADER_SIZE]
+            .try_into()
+            .expect("slice has exactly COUNT_SIZE bytes"),
+    ) as usize;
+
+    body[CHAIN_BODY_HEADER_SIZE..]
+        .chunks_exact(ENTRY_SIZE)
+        .take(count)
+        .map(|chunk| {
+            let logical =
+                PageId::from_le_bytes(chunk[..PAGE_ID_SIZE].try_into().expect("PAGE_ID_SIZE bytes"));
+            let physical = PageId::from_le_bytes(
+                chunk[PAGE_ID_SIZE..ENTRY_SIZE]
+                    .try_into()
+                    .expect("PAGE_ID_SIZE bytes"),
+            );
+            (logical, physical)
+        })
+        .collect()
+}
+
+fn write_entries(page: &mut Page, entries: &[(PageId, PageId)]) {
+    debug_assert!(entries.len() <= MAP_ENTRIES_PER_PAGE);
+
+    let body = page.data_mut();
+    #[allow(clippy::cast_possible_truncation)]
+    let count = entries.len() as u32;
+    body[PAGE_ID_SIZE..CHAIN_BODY_HEADER_SIZE].copy_from_slice(&count.to_le_bytes());
+
+    let entries_region = &mut body[CHAIN_BODY_HEADER_SIZE..];
+    for (chunk, &(logical, physical)) in entries_region.chunks_exact_mut(ENTRY_SIZE).zip(entries) {
+        chunk[..PAGE_ID_SIZE].copy_from_slice(&logical.to_le_bytes());
+        chunk[PAGE_ID_SIZE..ENTRY_SIZE].copy_from_slice(&physical.to_le_bytes());
+    }
+    for chunk in entries_region.chunks_exact_mut(ENTRY_SIZE).skip(entries.len()) {
+        chunk.fill(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn open_temp_file() -> File {
+        let temp = NamedTempFile::new().unwrap();
+        File::options().read(true).write(true).open(temp.path()).unwrap()
+    }
+
+    #[test]
+    fn test_create_mapping_and_read_back() {
+        let mut file = open_temp_file();
+        let mut allocator = PageAllocator::new(0);
+        let mut map = PageMap::new();
+
+        map.create_mapping(&mut file, &mut allocator, 1, PageType::Data)
+            .unwrap();
+        let mut page = map.read_page(&mut file, 1).unwrap();
+        assert_eq!(page.header().page_type, PageType::Data);
+
+        page.data_mut()[0] = 0x42;
+        map.write_page(&mut file, &mut allocator, 1, page).unwrap();
+
+        let read_back = map.read_page(&mut file, 1).unwrap();
+        assert_eq!(read_back.data()[0], 0x42);
+    }
+
+    #[test]
+    fn test_write_without_snapshot_reuses_physical_page() {
+        let mut file = open_temp_file();
+        let mut allocator = PageAllocator::new(0);
+        let mut map = PageMap::new();
+
+        let physical = map
+            .create_mapping(&mut file, &mut allocator, 1, PageType::Data)
+            .unwrap();
+        let page = map.read_page(&mut file, 1).unwrap();
+        map.write_page(&mut file, &mut allocator, 1, page).unwrap();
+
+        assert_eq!(map.translate(1), Some(physical));
+    }
+
+    #[test]
+    fn test_write_after_snapshot_allocates_new_physical_page() {
+        let mut file = open_temp_file();
+        let mut allocator = PageAllocator::new(0);
+        let mut map = PageMap::new();
+
+        let original_physical = map
+            .create_mapping(&mut file, &mut allocator, 1, PageType::Data)
+            .unwrap();
+
+        let snapshot = map.snapshot();
+        let page = map.read_page(&mut file, 1).unwrap();
+        map.write_page(&mut file, &mut allocator, 1, page).unwrap();
+
+        let new_physical = map.translate(1).unwrap();
+        assert_ne!(new_physical, original_physical);
+
+        // The snapshot's original physical page is untouched and still
+        // readable directly.
+        let original_page = read_page_from_file(&mut file, u64::from(original_physical)).unwrap();
+        assert_eq!(original_page.header().page_type, PageType::Data);
+
+        // It's also reachable through the public point-in-time read API.
+        let snapshotted_page = map.read_at(&mut file, &snapshot, 1).unwrap();
+        assert_eq!(snapshotted_page.header().page_id, original_physical);
+
+        drop(snapshot);
+    }
+
+    #[test]
+    fn test_read_at_after_multiple_writes_resolves_each_generation() {
+        let mut file = open_temp_file();
+        let mut allocator = PageAllocator::new(0);
+        let mut map = PageMap::new();
+
+        map.create_mapping(&mut file, &mut allocator, 1, PageType::Data)
+            .unwrap();
+
+        let snapshot_a = map.snapshot();
+        let physical_a = map.translate(1).unwrap();
+        let page = map.read_page(&mut file, 1).unwrap();
+        map.write_page(&mut file, &mut allocator, 1, page).unwrap();
+
+        let snapshot_b = map.snapshot();
+        let physical_b = map.translate(1).unwrap();
+        let page = map.read_page(&mut file, 1).unwrap();
+        map.write_page(&mut file, &mut allocator, 1, page).unwrap();
+
+        let physical_current = map.translate(1).unwrap();
+        assert_ne!(physical_a, physical_b);
+        assert_ne!(physical_b, physical_current);
+
+        assert_eq!(
+            map.read_at(&mut file, &snapshot_a, 1).unwrap().header().page_id,
+            physical_a
+        );
+        assert_eq!(
+            map.read_at(&mut file, &snapshot_b, 1).unwrap().header().page_id,
+            physical_b
+        );
+
+        drop(snapshot_a);
+        drop(snapshot_b);
+    }
+
+    #[test]
+    fn test_writes_after_snapshot_dropped_reuse_physical_page_again() {
+        let mut file = open_temp_file();
+        let mut allocator = PageAllocator::new(0);
+        let mut map = PageMap::new();
+
+        map.create_mapping(&mut file, &mut allocator, 1, PageType::Data)
+            .unwrap();
+
+        let snapshot = map.snapshot();
+        let page = map.read_page(&mut file, 1).unwrap();
+        map.write_page(&mut file, &mut allocator, 1, page).unwrap();
+        drop(snapshot);
+
+        let physical_after_cow = map.translate(1).unwrap();
+        let page = map.read_page(&mut file, 1).unwrap();
+        map.write_page(&mut file, &mut allocator, 1, page).unwrap();
+
+        assert_eq!(map.translate(1), Some(physical_after_cow));
+    }
+
+    #[test]
+    fn test_write_to_unmapped_logical_page_is_not_found() {
+        let mut file = open_temp_file();
+        let mut allocator = PageAllocator::new(0);
+        let mut map = PageMap::new();
+
+        let page = Page::new();
+        let result = map.write_page(&mut file, &mut allocator, 99, page);
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[test]
+    fn test_flush_and_load_roundtrip() {
+        let mut file = open_temp_file();
+        let mut allocator = PageAllocator::new(0);
+        let mut map = PageMap::new();
+
+        for logical in 1..=3 {
+            map.create_mapping(&mut file, &mut allocator, logical, PageType::Data)
+                .unwrap();
+        }
+
+        let head = map.flush(&mut file, &mut allocator).unwrap();
+        let loaded = PageMap::load(&mut file, head).unwrap();
+
+        for logical in 1..=3 {
+            assert_eq!(loaded.translate(logical), map.translate(logical));
+        }
+    }
+
+    #[test]
+    fn test_flush_spans_multiple_chain_pages() {
+        let mut file = open_temp_file();
+        let mut allocator = PageAllocator::new(0);
+        let mut map = PageMap::new();
+
+        let logical_count = MAP_ENTRIES_PER_PAGE + 5;
+        for logical in 1..=logical_count as PageId {
+            map.create_mapping(&mut file, &mut allocator, logical, PageType::Data)
+                .unwrap();
+        }
+
+        let head = map.flush(&mut file, &mut allocator).unwrap();
+        let loaded = PageMap::load(&mut file, head).unwrap();
+
+        for logical in 1..=logical_count as PageId {
+            assert_eq!(loaded.translate(logical), map.translate(logical));
+        }
+    }
+}
@@ -24,6 +24,9 @@ pub enum PageType {
     FreeList = 0x08,
     /// Bloom filter page - for fast existence checks
     BloomFilter = 0x09,
+    /// Page map page - part of the chain persisting the logical-to-physical
+    /// page mapping (see [`crate::storage::page_map`])
+    PageMap = 0x0A,
 }
 
 impl TryFrom<u8> for PageType {
@@ -40,6 +43,7 @@ impl TryFrom<u8> for PageType {
             0x07 => Ok(PageType::Overflow),
             0x08 => Ok(PageType::FreeList),
             0x09 => Ok(PageType::BloomFilter),
+            0x0A => Ok(PageType::PageMap),
             _ => Err(Error::InvalidPageType(value)),
         }
     }
@@ -56,6 +60,11 @@ impl PageType {
         matches!(self, PageType::FreeList)
     }
 
+    /// Check if this is a page map page
+    pub fn is_page_map(&self) -> bool {
+        matches!(self, PageType::PageMap)
+    }
+
     /// Check if this is an overflow page
     pub fn is_overflow(&self) -> bool {
         matches!(self, PageType::Overflow)
@@ -94,6 +103,7 @@ mod tests {
         assert_eq!(PageType::Overflow as u8, 0x07);
         assert_eq!(PageType::FreeList as u8, 0x08);
         assert_eq!(PageType::BloomFilter as u8, 0x09);
+        assert_eq!(PageType::PageMap as u8, 0x0A);
     }
 
     #[test]
@@ -107,13 +117,14 @@ mod tests {
         assert_eq!(PageType::try_from(0x07u8).unwrap(), PageType::Overflow);
         assert_eq!(PageType::try_from(0x08u8).unwrap(), PageType::FreeList);
         assert_eq!(PageType::try_from(0x09u8).unwrap(), PageType::BloomFilter);
+        assert_eq!(PageType::try_from(0x0Au8).unwrap(), PageType::PageMap);
     }
 
     #[test]
     fn test_page_type_from_u8_invalid() {
-        // Test invalid values: 0 and anything > 9
+        // Test invalid values: 0 and anything > 0x0A
         assert!(PageType::try_from(0u8).is_err());
-        for invalid in 10u8..=255u8 {
+        for invalid in 0x0Bu8..=255u8 {
             match PageType::try_from(invalid) {
                 Err(Error::InvalidPageType(val)) => assert_eq!(val, invalid),
                 _ => panic!("Expected InvalidPageType error for value {invalid}"),
@@ -134,6 +145,10 @@ mod tests {
         assert!(PageType::FreeList.is_free_list());
         assert!(!PageType::BTreeLeaf.is_free_list());
 
+        // Test is_page_map
+        assert!(PageType::PageMap.is_page_map());
+        assert!(!PageType::FreeList.is_page_map());
+
         // Test is_overflow
         assert!(PageType::Overflow.is_overflow());
         assert!(!PageType::BTreeLeaf.is_overflow());
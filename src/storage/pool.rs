@@ -0,0 +1,345 @@
+//! Fixed-capacity page buffer pool and CLOCK-evicted page cache
+//!
+//! `Page::new()` builds a fresh `PAGE_SIZE`-aligned buffer on every
+//! call, and the plain read path (`read_page_from_file`) builds a new
+//! [`Page`] per read. [`PagePool`] preallocates a fixed arena of
+//! `capacity` page buffers once and hands them out by slot index via
+//! `acquire`/`release` against a free list, so steady-state operation
+//! does no further heap allocation. [`PageCache`] layers a `page_id`-keyed
+//! cache on top with CLOCK (second-chance) eviction, writing dirty pages
+//! back through `write_page_to_file` before their slot is reused.
+
+use crate::common::error::Error;
+use crate::storage::page::Page;
+use crate::storage::page_constants::PageId;
+use crate::storage::page_io::{read_page_from_file, write_page_to_file};
+use std::collections::HashMap;
+use std::fs::File;
+
+/// A fixed-capacity arena of `PAGE_SIZE`-aligned page buffers, handed out
+/// by slot index against a free list.
+///
+/// Preallocating the whole arena up front means `acquire`/`release`
+/// themselves never allocate or free memory; only the initial
+/// [`PagePool::new`] call does.
+pub struct PagePool {
+    buffers: Vec<Page>,
+    free: Vec<usize>,
+}
+
+impl PagePool {
+    /// Preallocate a pool of `capacity` zeroed pages.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buffers: (0..capacity).map(|_| Page::new()).collect(),
+            free: (0..capacity).rev().collect(),
+        }
+    }
+
+    /// Total number of buffers in the pool.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Number of buffers currently available to `acquire`.
+    #[must_use]
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Take ownership of a free buffer slot, or `None` if the pool is
+    /// fully checked out.
+    pub fn acquire(&mut self) -> Option<usize> {
+        self.free.pop()
+    }
+
+    /// Return a slot to the free list. The buffer's contents are left as
+    /// they are; the next `acquire` of this slot will see stale data
+    /// until the caller overwrites it.
+    pub fn release(&mut self, slot: usize) {
+        debug_assert!(slot < self.buffers.len());
+        self.free.push(slot);
+    }
+
+    /// Borrow the page stored at `slot`.
+    #[must_use]
+    pub fn page(&self, slot: usize) -> &Page {
+        &self.buffers[slot]
+    }
+
+    /// Mutably borrow the page stored at `slot`.
+    pub fn page_mut(&mut self, slot: usize) -> &mut Page {
+        &mut self.buffers[slot]
+    }
+}
+
+/// A `page_id`-keyed cache of pages backed by a [`PagePool`], evicting
+/// with the CLOCK (second-chance) algorithm once the pool fills up.
+///
+/// A miss reads through [`read_page_from_file`]; evicting a dirty slot
+/// writes it back through [`write_page_to_file`] first, so no write is
+/// ever silently dropped.
+pub struct PageCache {
+    pool: PagePool,
+    slot_for_page: HashMap<PageId, usize>,
+    page_for_slot: Vec<Option<PageId>>,
+    referenced: Vec<bool>,
+    dirty: Vec<bool>,
+    clock_hand: usize,
+}
+
+impl PageCache {
+    /// Create a cache backed by a pool of `capacity` page buffers.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            pool: PagePool::new(capacity),
+            slot_for_page: HashMap::new(),
+            page_for_slot: vec![None; capacity],
+            referenced: vec![false; capacity],
+            dirty: vec![false; capacity],
+            clock_hand: 0,
+        }
+    }
+
+    /// Number of page buffers backing this cache.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.pool.capacity()
+    }
+
+    /// Number of pages currently resident in the cache.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.slot_for_page.len()
+    }
+
+    /// True if no pages are resident.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.slot_for_page.is_empty()
+    }
+
+    /// Fetch `page_id`, reading it from `file` on a cache miss.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a miss requires reading from `file` and that
+    /// read fails (including a [`Error::ChecksumMismatch`]), or if
+    /// evicting a dirty page to make room fails to write back.
+    pub fn get_page(&mut self, file: &mut File, page_id: PageId) -> Result<&Page, Error> {
+        if let Some(&slot) = self.slot_for_page.get(&page_id) {
+            self.referenced[slot] = true;
+            return Ok(self.pool.page(slot));
+        }
+
+        let slot = self.slot_for_load(file)?;
+        let page = read_page_from_file(file, u64::from(page_id))?;
+        *self.pool.page_mut(slot) = page;
+        self.page_for_slot[slot] = Some(page_id);
+        self.referenced[slot] = true;
+        self.dirty[slot] = false;
+        self.slot_for_page.insert(page_id, slot);
+
+        Ok(self.pool.page(slot))
+    }
+
+    /// Mutably fetch `page_id`, marking it dirty so it's written back on
+    /// eviction (or via [`PageCache::flush`]).
+    ///
+    /// # Errors
+    ///
+    /// See [`PageCache::get_page`].
+    pub fn get_page_mut(&mut self, file: &mut File, page_id: PageId) -> Result<&mut Page, Error> {
+        self.get_page(file, page_id)?;
+        let slot = self.slot_for_page[&page_id];
+        self.dirty[slot] = true;
+        Ok(self.pool.page_mut(slot))
+    }
+
+    /// Write every dirty resident page back to `file` and clear their
+    /// dirty bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any write fails.
+    pub fn flush(&mut self, file: &mut File) -> Result<(), Error> {
+        for slot in 0..self.pool.capacity() {
+            if self.dirty[slot] {
+                if let Some(page_id) = self.page_for_slot[slot] {
+                    self.pool.page_mut(slot).calculate_checksum()?;
+                    write_page_to_file(file, u64::from(page_id), self.pool.page(slot))?;
+                    self.dirty[slot] = false;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Find a slot to load a fresh page into: a free one if the pool
+    /// hasn't filled up yet, otherwise a CLOCK-evicted resident slot.
+    fn slot_for_load(&mut self, file: &mut File) -> Result<usize, Error> {
+        if let Some(slot) = self.pool.acquire() {
+            return Ok(slot);
+        }
+        self.evict_one(file)
+    }
+
+    /// Run the CLOCK (second-chance) algorithm: walk slots starting at
+    /// `clock_hand`, clearing each referenced bit in turn, and evict the
+    /// first slot found already unreferenced. Terminates within two
+    /// passes over the pool since every slot's bit gets cleared at most
+    /// once before this slot's own bit is re-checked.
+    fn evict_one(&mut self, file: &mut File) -> Result<usize, Error> {
+        let capacity = self.pool.capacity();
+        loop {
+            let slot = self.clock_hand;
+            self.clock_hand = (self.clock_hand + 1) % capacity;
+
+            if self.referenced[slot] {
+                self.referenced[slot] = false;
+                continue;
+            }
+
+            if self.dirty[slot] {
+                if let Some(page_id) = self.page_for_slot[slot] {
+                    self.pool.page_mut(slot).calculate_checksum()?;
+                    write_page_to_file(file, u64::from(page_id), self.pool.page(slot))?;
+                }
+                self.dirty[slot] = false;
+            }
+
+            if let Some(page_id) = self.page_for_slot[slot].take() {
+                self.slot_for_page.remove(&page_id);
+            }
+
+            return Ok(slot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::page_constants::PAGE_SIZE;
+    use crate::storage::page_type::PageType;
+    use tempfile::NamedTempFile;
+
+    fn write_test_page(file: &mut File, page_id: PageId, marker: u8) {
+        let mut page = Page::new();
+        page.header_mut().page_type = PageType::Data;
+        page.header_mut().page_id = page_id;
+        page.data_mut()[0] = marker;
+        page.calculate_checksum().unwrap();
+        write_page_to_file(file, u64::from(page_id), &page).unwrap();
+    }
+
+    #[test]
+    fn test_pool_acquire_release_reuses_slots() {
+        let mut pool = PagePool::new(2);
+        assert_eq!(pool.capacity(), 2);
+        assert_eq!(pool.available(), 2);
+
+        let a = pool.acquire().unwrap();
+        let b = pool.acquire().unwrap();
+        assert!(pool.acquire().is_none());
+        assert_eq!(pool.available(), 0);
+
+        pool.release(a);
+        assert_eq!(pool.available(), 1);
+        let reused = pool.acquire().unwrap();
+        assert_eq!(reused, a);
+
+        pool.release(b);
+        pool.release(reused);
+    }
+
+    #[test]
+    fn test_pool_pages_stay_page_size_aligned() {
+        let pool = PagePool::new(4);
+        for slot in 0..pool.capacity() {
+            assert_eq!(pool.page(slot).raw().len(), PAGE_SIZE);
+        }
+    }
+
+    #[test]
+    fn test_cache_miss_then_hit() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut file = File::create(temp_file.path()).unwrap();
+        write_test_page(&mut file, 0, 0xAB);
+
+        let mut file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(temp_file.path())
+            .unwrap();
+        let mut cache = PageCache::new(2);
+
+        assert!(cache.is_empty());
+        let page = cache.get_page(&mut file, 0).unwrap();
+        assert_eq!(page.data()[0], 0xAB);
+        assert_eq!(cache.len(), 1);
+
+        // Second fetch is a hit: no second read needed and the same
+        // content comes back.
+        let page = cache.get_page(&mut file, 0).unwrap();
+        assert_eq!(page.data()[0], 0xAB);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_cache_evicts_when_full_and_writes_back_dirty_page() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut file = File::create(temp_file.path()).unwrap();
+        for page_id in 0..3 {
+            write_test_page(&mut file, page_id, page_id as u8);
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(temp_file.path())
+            .unwrap();
+        let mut cache = PageCache::new(2);
+
+        // Load page 1 (and dirty it) before page 0, so CLOCK's sweep
+        // reaches its slot first once both are referenced.
+        cache.get_page_mut(&mut file, 1).unwrap().data_mut()[0] = 0xFF;
+        cache.get_page(&mut file, 0).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        // Both slots are referenced; loading a third page forces an
+        // eviction, writing back page 1's dirty modification first.
+        cache.get_page(&mut file, 2).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        drop(cache);
+        let mut verify_file = File::open(temp_file.path()).unwrap();
+        let reread = read_page_from_file(&mut verify_file, 1).unwrap();
+        assert_eq!(reread.data()[0], 0xFF);
+    }
+
+    #[test]
+    fn test_cache_flush_writes_all_dirty_pages() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut file = File::create(temp_file.path()).unwrap();
+        write_test_page(&mut file, 0, 0x00);
+
+        let mut file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(temp_file.path())
+            .unwrap();
+        let mut cache = PageCache::new(2);
+
+        cache.get_page_mut(&mut file, 0).unwrap().data_mut()[0] = 0x42;
+        cache.flush(&mut file).unwrap();
+
+        drop(cache);
+        let mut verify_file = File::open(temp_file.path()).unwrap();
+        let reread = read_page_from_file(&mut verify_file, 0).unwrap();
+        assert_eq!(reread.data()[0], 0x42);
+    }
+}
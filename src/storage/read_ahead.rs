@@ -0,0 +1,135 @@
+//! Sequential-access read-ahead window tracking
+//!
+//! [`ReadAhead`] watches a stream of single-page accesses and grows a
+//! prefetch window while they stay sequential (page N, then N+1, then
+//! N+2, ...), so a caller can batch the next several pages into one
+//! [`crate::storage::page_io::read_pages_at`] call instead of one
+//! syscall per page. The moment an access breaks the run — a random
+//! seek, a jump back, a gap — the window collapses back to a single
+//! page, since prefetching pages nobody asked for wastes I/O on
+//! workloads that aren't actually scanning.
+//!
+//! This module only tracks *when* to grow or shrink the window; it does
+//! not perform any I/O itself.
+
+use crate::storage::page_constants::PageId;
+
+const MIN_WINDOW: usize = 1;
+const MAX_WINDOW: usize = 64;
+
+/// Tracks one access stream's sequentiality and the resulting prefetch
+/// window size.
+#[derive(Debug, Clone, Copy)]
+pub struct ReadAhead {
+    last_page_id: Option<PageId>,
+    window: usize,
+}
+
+impl ReadAhead {
+    /// A fresh tracker with no history and the minimum window.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            last_page_id: None,
+            window: MIN_WINDOW,
+        }
+    }
+
+    /// Current prefetch window size, in pages.
+    #[must_use]
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Record an access to `page_id`, growing the window on a sequential
+    /// hit (this access immediately follows the last one) and collapsing
+    /// it back to [`MIN_WINDOW`] on a random access, then returning the
+    /// `(start_page_id, count)` a caller should prefetch next.
+    pub fn record_access(&mut self, page_id: PageId) -> (PageId, usize) {
+        let sequential = self.last_page_id.is_some() && self.last_page_id == page_id.checked_sub(1);
+
+        self.window = if sequential {
+            (self.window * 2).min(MAX_WINDOW)
+        } else {
+            MIN_WINDOW
+        };
+        self.last_page_id = Some(page_id);
+
+        (page_id + 1, self.window)
+    }
+
+    /// Reset to a fresh tracker, discarding any learned window.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for ReadAhead {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_tracker_starts_at_minimum_window() {
+        let ra = ReadAhead::new();
+        assert_eq!(ra.window(), MIN_WINDOW);
+    }
+
+    #[test]
+    fn test_sequential_accesses_grow_the_window() {
+        let mut ra = ReadAhead::new();
+        let (_, w0) = ra.record_access(0);
+        let (_, w1) = ra.record_access(1);
+        let (_, w2) = ra.record_access(2);
+        let (_, w3) = ra.record_access(3);
+
+        assert!(w1 > w0);
+        assert!(w2 > w1);
+        assert!(w3 > w2);
+    }
+
+    #[test]
+    fn test_window_caps_at_maximum() {
+        let mut ra = ReadAhead::new();
+        for page_id in 0..20 {
+            ra.record_access(page_id);
+        }
+        assert_eq!(ra.window(), MAX_WINDOW);
+    }
+
+    #[test]
+    fn test_random_access_collapses_window() {
+        let mut ra = ReadAhead::new();
+        ra.record_access(0);
+        ra.record_access(1);
+        ra.record_access(2);
+        assert!(ra.window() > MIN_WINDOW);
+
+        ra.record_access(100);
+        assert_eq!(ra.window(), MIN_WINDOW);
+    }
+
+    #[test]
+    fn test_record_access_suggests_next_contiguous_run() {
+        let mut ra = ReadAhead::new();
+        let (start, count) = ra.record_access(5);
+        assert_eq!(start, 6);
+        assert_eq!(count, MIN_WINDOW);
+    }
+
+    #[test]
+    fn test_reset_discards_learned_window() {
+        let mut ra = ReadAhead::new();
+        ra.record_access(0);
+        ra.record_access(1);
+        assert!(ra.window() > MIN_WINDOW);
+
+        ra.reset();
+        assert_eq!(ra.window(), MIN_WINDOW);
+    }
+}
@@ -0,0 +1,302 @@
+//! Page repair tool driven by checksum verification
+//!
+//! Following the repair/metadata-dump pattern common to filesystem
+//! recovery tooling, [`scan`] walks a database file page-by-page using
+//! [`Page::verify_checksum`] and reports which `page_id`s are corrupt
+//! without modifying anything, and [`repair`] copies every verifiably-good
+//! page into a fresh file, substituting a placeholder for each corrupt one.
+//!
+//! This tree has no mechanism yet for maintaining a redundant/mirror copy
+//! of a page (no replication or parity subsystem exists alongside
+//! [`crate::storage::swap`] or [`crate::storage::page_map`]), so there is
+//! nothing for `repair` to substitute a corrupt page *with* beyond a
+//! zeroed placeholder; [`MirrorSource`] is the extension point a future
+//! redundancy subsystem would implement to plug a real recovery path in
+//! without changing `repair`'s own logic.
+
+use crate::common::error::Error;
+use crate::storage::page::Page;
+use crate::storage::page_constants::PAGE_SIZE;
+use crate::storage::page_io::{read_page_at, write_page_at};
+use std::fs::File;
+use std::path::Path;
+
+/// A source of known-good replacement bytes for a corrupt page, e.g. a
+/// mirrored replica or a parity-reconstructed copy.
+///
+/// No implementation of this exists in the tree yet; it's the seam
+/// [`repair`] calls through so a future redundancy subsystem can supply
+/// real recovered pages instead of [`repair`] falling back to a zeroed
+/// placeholder for every corrupt page it finds.
+pub trait MirrorSource {
+    /// Return a known-good copy of `page_id`, if one is available.
+    fn recover(&self, page_id: u64) -> Option<Page>;
+}
+
+/// How a single corrupt page was handled by [`repair`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepairAction {
+    /// A [`MirrorSource`] supplied a known-good replacement.
+    RecoveredFromMirror,
+    /// No mirror was available; the page was replaced with a zeroed
+    /// placeholder.
+    ZeroedPlaceholder,
+}
+
+/// One page that failed checksum verification, and what was done about
+/// it (populated by [`repair`]; left `None` by [`scan`], which never
+/// writes anything).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorruptPage {
+    /// ID of the corrupt page.
+    pub page_id: u64,
+    /// How the page was handled, if `repair` has run.
+    pub action: Option<RepairAction>,
+}
+
+/// Result of scanning or repairing a database file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RepairReport {
+    /// Total number of pages examined.
+    pub pages_scanned: u64,
+    /// Every page that failed checksum verification, in ascending
+    /// `page_id` order.
+    pub corrupt_pages: Vec<CorruptPage>,
+}
+
+impl RepairReport {
+    /// Whether every page in the file verified cleanly.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.corrupt_pages.is_empty()
+    }
+}
+
+fn page_count(file: &File) -> Result<u64, Error> {
+    let len = file.metadata()?.len();
+    Ok(len / PAGE_SIZE as u64)
+}
+
+/// Scan `path` page-by-page and report which pages are corrupt, without
+/// modifying the file.
+///
+/// # Errors
+///
+/// Returns an error if `path` can't be opened or its metadata/pages can't
+/// be read (other than a checksum mismatch, which is recorded in the
+/// report rather than returned as an error).
+pub fn scan<P: AsRef<Path>>(path: P) -> Result<RepairReport, Error> {
+    let file = File::open(path)?;
+    let pages_scanned = page_count(&file)?;
+
+    let mut corrupt_pages = Vec::new();
+    for page_id in 0..pages_scanned {
+        match read_page_at(&file, page_id) {
+            Ok(_) => {}
+            Err(err) if err.is_checksum_mismatch() => corrupt_pages.push(CorruptPage {
+                page_id,
+                action: None,
+            }),
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(RepairReport {
+        pages_scanned,
+        corrupt_pages,
+    })
+}
+
+/// Copy every verifiably-good page from `old_path` into a fresh file at
+/// `new_path`, substituting a recovered copy from `mirror` (if one is
+/// available) or a zeroed placeholder for each corrupt page.
+///
+/// `old_path` is opened read-only and is never written to; `new_path` is
+/// created fresh (failing if it already exists) so a partially-repaired
+/// run can never clobber a good source file.
+///
+/// # Errors
+///
+/// Returns an error if `old_path` can't be opened for reading, if
+/// `new_path` can't be created, or if a read/write against either file
+/// fails for a reason other than a source checksum mismatch.
+pub fn repair<P: AsRef<Path>>(
+    old_path: P,
+    new_path: P,
+    mirror: Option<&dyn MirrorSource>,
+) -> Result<RepairReport, Error> {
+    let source = File::open(&old_path)?;
+    let pages_scanned = page_count(&source)?;
+
+    let dest = File::options()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&new_path)?;
+
+    let mut corrupt_pages = Vec::new();
+    for page_id in 0..pages_scanned {
+        match read_page_at(&source, page_id) {
+            Ok(page) => write_page_at(&dest, page_id, &page)?,
+            Err(err) if !err.is_checksum_mismatch() => return Err(err),
+            Err(_) => {
+                let (mut page, action) = match mirror.and_then(|m| m.recover(page_id)) {
+                    Some(recovered) => (recovered, RepairAction::RecoveredFromMirror),
+                    None => (Page::new(), RepairAction::ZeroedPlaceholder),
+                };
+                // A mirror-recovered page should already carry a valid
+                // checksum, but recompute unconditionally so a
+                // `MirrorSource` impl can't silently ship a corrupt
+                // "repair" - and so the zeroed placeholder verifies too.
+                page.calculate_checksum()?;
+                write_page_at(&dest, page_id, &page)?;
+                corrupt_pages.push(CorruptPage {
+                    page_id,
+                    action: Some(action),
+                });
+            }
+        }
+    }
+
+    Ok(RepairReport {
+        pages_scanned,
+        corrupt_pages,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::page_io::write_page_at;
+    use crate::storage::page_type::PageType;
+    use tempfile::NamedTempFile;
+
+    fn sample_page(page_id: u64) -> Page {
+        let mut page = Page::new();
+        page.header_mut().page_type = PageType::Data;
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            page.header_mut().page_id = page_id as _;
+        }
+        page.calculate_checksum().unwrap();
+        page
+    }
+
+    fn open_rw(path: &Path) -> File {
+        File::options().read(true).write(true).open(path).unwrap()
+    }
+
+    #[test]
+    fn test_scan_reports_no_corruption_on_clean_file() {
+        let temp = NamedTempFile::new().unwrap();
+        let file = open_rw(temp.path());
+        for page_id in 0..4 {
+            write_page_at(&file, page_id, &sample_page(page_id)).unwrap();
+        }
+
+        let report = scan(temp.path()).unwrap();
+        assert_eq!(report.pages_scanned, 4);
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn test_scan_flags_corrupt_page() {
+        let temp = NamedTempFile::new().unwrap();
+        let file = open_rw(temp.path());
+        for page_id in 0..3 {
+            write_page_at(&file, page_id, &sample_page(page_id)).unwrap();
+        }
+        // Corrupt page 1's bytes directly, bypassing checksum recalculation.
+        let mut corrupt = sample_page(1);
+        corrupt.data_mut()[0] ^= 0xFF;
+        write_page_at(&file, 1, &corrupt).unwrap();
+
+        let report = scan(temp.path()).unwrap();
+        assert_eq!(
+            report.corrupt_pages,
+            vec![CorruptPage {
+                page_id: 1,
+                action: None
+            }]
+        );
+    }
+
+    #[test]
+    fn test_repair_copies_good_pages_and_zeroes_corrupt_ones() {
+        let old = NamedTempFile::new().unwrap();
+        let new = NamedTempFile::new().unwrap();
+        let new_path = new.path().to_path_buf();
+        // repair() creates new_path fresh; drop the placeholder so create_new succeeds.
+        drop(new);
+        std::fs::remove_file(&new_path).unwrap();
+
+        let file = open_rw(old.path());
+        for page_id in 0..3 {
+            write_page_at(&file, page_id, &sample_page(page_id)).unwrap();
+        }
+        let mut corrupt = sample_page(1);
+        corrupt.data_mut()[0] ^= 0xFF;
+        write_page_at(&file, 1, &corrupt).unwrap();
+
+        let report = repair(old.path(), new_path.as_path(), None).unwrap();
+        assert_eq!(
+            report.corrupt_pages,
+            vec![CorruptPage {
+                page_id: 1,
+                action: Some(RepairAction::ZeroedPlaceholder)
+            }]
+        );
+
+        let repaired = open_rw(&new_path);
+        assert_eq!(read_page_at(&repaired, 0).unwrap().header().page_id, 0);
+        assert_eq!(read_page_at(&repaired, 2).unwrap().header().page_id, 2);
+        // Page 1 was replaced with a freshly-initialized, checksum-valid placeholder.
+        let placeholder = read_page_at(&repaired, 1).unwrap();
+        assert_eq!(placeholder.header().page_id, 0);
+
+        std::fs::remove_file(&new_path).ok();
+    }
+
+    #[test]
+    fn test_repair_uses_mirror_when_available() {
+        struct StaticMirror(Page);
+        impl MirrorSource for StaticMirror {
+            fn recover(&self, page_id: u64) -> Option<Page> {
+                if page_id == 1 {
+                    let mut copy = Page::new();
+                    copy.raw_mut().copy_from_slice(self.0.raw());
+                    Some(copy)
+                } else {
+                    None
+                }
+            }
+        }
+
+        let old = NamedTempFile::new().unwrap();
+        let new = NamedTempFile::new().unwrap();
+        let new_path = new.path().to_path_buf();
+        drop(new);
+        std::fs::remove_file(&new_path).unwrap();
+
+        let file = open_rw(old.path());
+        write_page_at(&file, 0, &sample_page(0)).unwrap();
+        let mut corrupt = sample_page(1);
+        corrupt.data_mut()[0] ^= 0xFF;
+        write_page_at(&file, 1, &corrupt).unwrap();
+
+        let mirror = StaticMirror(sample_page(1));
+        let report = repair(old.path(), new_path.as_path(), Some(&mirror)).unwrap();
+        assert_eq!(
+            report.corrupt_pages,
+            vec![CorruptPage {
+                page_id: 1,
+                action: Some(RepairAction::RecoveredFromMirror)
+            }]
+        );
+
+        let repaired = open_rw(&new_path);
+        assert_eq!(read_page_at(&repaired, 1).unwrap().header().page_id, 1);
+
+        std::fs::remove_file(&new_path).ok();
+    }
+}
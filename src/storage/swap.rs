@@ -0,0 +1,265 @@
+//! Page-eviction swap file for spilling pages under memory pressure
+//!
+//! [`crate::storage::pool::PageCache`] evicts pages by CLOCK (second
+//! chance) from its fixed-capacity arena, writing dirty ones back to the
+//! database file. [`SwapFile`] gives an eviction path an alternative
+//! destination — a separate scratch file — for pages that should be
+//! spilled out of memory without yet being written back to their real
+//! home in the database (e.g. uncommitted working-set pages that aren't
+//! durable yet). [`swap_out`](SwapFile::swap_out) stashes a page's bytes
+//! in a reused or freshly allocated slot; [`swap_in`](SwapFile::swap_in)
+//! reads it back, verifying its checksum, and frees the slot.
+//!
+//! A `swapped_out` bitmap mirrors the `slot_for_page` map so callers can
+//! cheaply ask "is this page currently out on disk?" without a hash
+//! lookup on the hot path. [`swap_out_batch`] handles the common case of
+//! evicting a contiguous run of dirty pages at once with a single
+//! positioned write instead of one syscall per page.
+
+use crate::common::error::Error;
+use crate::storage::page::Page;
+use crate::storage::page_constants::PageId;
+use crate::storage::page_io::{read_page_at, write_page_at, write_pages_at};
+use std::collections::HashMap;
+use std::fs::File;
+
+const BITS_PER_WORD: usize = u64::BITS as usize;
+
+#[allow(clippy::cast_possible_truncation)]
+fn word_and_bit(page_id: PageId) -> (usize, u32) {
+    let index = page_id as usize;
+    (index / BITS_PER_WORD, (index % BITS_PER_WORD) as u32)
+}
+
+/// Spills pages to a scratch file when memory pressure requires eviction,
+/// tracking which logical pages are currently out on disk.
+pub struct SwapFile {
+    file: File,
+    swapped_out: Vec<u64>,
+    slot_for_page: HashMap<PageId, usize>,
+    free_slots: Vec<usize>,
+    next_slot: usize,
+}
+
+impl SwapFile {
+    /// Wrap an (empty or reused) scratch file as a swap file with no
+    /// resident bookkeeping yet.
+    #[must_use]
+    pub fn new(file: File) -> Self {
+        Self {
+            file,
+            swapped_out: Vec::new(),
+            slot_for_page: HashMap::new(),
+            free_slots: Vec::new(),
+            next_slot: 0,
+        }
+    }
+
+    /// Whether `page_id` is currently swapped out (its only copy lives in
+    /// this swap file, not in memory).
+    #[must_use]
+    pub fn is_swapped_out(&self, page_id: PageId) -> bool {
+        let (word, bit) = word_and_bit(page_id);
+        self.swapped_out
+            .get(word)
+            .is_some_and(|w| w & (1 << bit) != 0)
+    }
+
+    fn mark_swapped_out(&mut self, page_id: PageId) {
+        let (word, bit) = word_and_bit(page_id);
+        if word >= self.swapped_out.len() {
+            self.swapped_out.resize(word + 1, 0);
+        }
+        self.swapped_out[word] |= 1 << bit;
+    }
+
+    fn mark_resident(&mut self, page_id: PageId) {
+        let (word, bit) = word_and_bit(page_id);
+        if let Some(w) = self.swapped_out.get_mut(word) {
+            *w &= !(1 << bit);
+        }
+    }
+
+    fn allocate_slot(&mut self, page_id: PageId) -> usize {
+        *self.slot_for_page.entry(page_id).or_insert_with(|| {
+            self.free_slots.pop().unwrap_or_else(|| {
+                let slot = self.next_slot;
+                self.next_slot += 1;
+                slot
+            })
+        })
+    }
+
+    /// Spill `page` (logically `page_id`) to a reused or freshly
+    /// allocated swap slot.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the positioned write to the swap file fails.
+    pub fn swap_out(&mut self, page_id: PageId, page: &Page) -> Result<(), Error> {
+        let slot = self.allocate_slot(page_id);
+        write_page_at(&self.file, slot as u64, page)?;
+        self.mark_swapped_out(page_id);
+        Ok(())
+    }
+
+    /// Spill a contiguous run of pages in one positioned write, rather
+    /// than one syscall per page.
+    ///
+    /// This always allocates a fresh contiguous run of slots at the end
+    /// of the swap file rather than reusing scattered freed slots (which
+    /// a contiguous write fundamentally can't target); freed slots are
+    /// still reused by the single-page [`swap_out`](Self::swap_out)
+    /// path. This is a deliberate simplification, the same trade-off
+    /// [`crate::storage::page_map::PageMap`] makes for its own flush.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the positioned write to the swap file fails.
+    pub fn swap_out_batch(&mut self, pages: &[(PageId, Page)]) -> Result<(), Error> {
+        if pages.is_empty() {
+            return Ok(());
+        }
+
+        let start_slot = self.next_slot;
+        self.next_slot += pages.len();
+
+        let page_copies: Vec<Page> = pages
+            .iter()
+            .map(|(_, page)| {
+                let mut copy = Page::new();
+                copy.raw_mut().copy_from_slice(page.raw());
+                copy
+            })
+            .collect();
+        write_pages_at(&self.file, start_slot as u64, &page_copies)?;
+
+        for (i, &(page_id, _)) in pages.iter().enumerate() {
+            self.slot_for_page.insert(page_id, start_slot + i);
+            self.mark_swapped_out(page_id);
+        }
+
+        Ok(())
+    }
+
+    /// Read `page_id` back from its swap slot, verifying its checksum,
+    /// and free the slot for reuse.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotFound`] if `page_id` isn't currently swapped
+    /// out, or an error if the positioned read or checksum verification
+    /// fails.
+    pub fn swap_in(&mut self, page_id: PageId) -> Result<Page, Error> {
+        let slot = self
+            .slot_for_page
+            .remove(&page_id)
+            .ok_or_else(|| Error::not_found(format!("page {page_id} is not swapped out")))?;
+
+        let page = read_page_at(&self.file, slot as u64)?;
+        self.free_slots.push(slot);
+        self.mark_resident(page_id);
+        Ok(page)
+    }
+
+    /// Discard all swapped-out pages and truncate the swap file back to
+    /// empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if truncating the underlying file fails.
+    pub fn clear(&mut self) -> Result<(), Error> {
+        self.file.set_len(0)?;
+        self.swapped_out.clear();
+        self.slot_for_page.clear();
+        self.free_slots.clear();
+        self.next_slot = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::page_type::PageType;
+    use tempfile::NamedTempFile;
+
+    fn open_temp_file() -> File {
+        let temp = NamedTempFile::new().unwrap();
+        File::options()
+            .read(true)
+            .write(true)
+            .open(temp.path())
+            .unwrap()
+    }
+
+    fn sample_page(page_id: PageId) -> Page {
+        let mut page = Page::new();
+        page.header_mut().page_type = PageType::Data;
+        page.header_mut().page_id = page_id;
+        page.calculate_checksum().unwrap();
+        page
+    }
+
+    #[test]
+    fn test_swap_out_then_in_roundtrips() {
+        let mut swap = SwapFile::new(open_temp_file());
+        let page = sample_page(7);
+
+        swap.swap_out(7, &page).unwrap();
+        assert!(swap.is_swapped_out(7));
+
+        let read_back = swap.swap_in(7).unwrap();
+        assert_eq!(read_back.header().page_id, 7);
+        assert!(!swap.is_swapped_out(7));
+    }
+
+    #[test]
+    fn test_swap_in_missing_page_is_not_found() {
+        let mut swap = SwapFile::new(open_temp_file());
+        let err = swap.swap_in(42).unwrap_err();
+        assert!(matches!(err, Error::NotFound(_)));
+    }
+
+    #[test]
+    fn test_slots_are_reused_after_swap_in() {
+        let mut swap = SwapFile::new(open_temp_file());
+        swap.swap_out(1, &sample_page(1)).unwrap();
+        swap.swap_in(1).unwrap();
+
+        assert_eq!(swap.next_slot, 1);
+        swap.swap_out(2, &sample_page(2)).unwrap();
+        // Reused the freed slot rather than growing the file further.
+        assert_eq!(swap.next_slot, 1);
+    }
+
+    #[test]
+    fn test_swap_out_batch_roundtrips_contiguous_run() {
+        let mut swap = SwapFile::new(open_temp_file());
+        let pages = vec![(10, sample_page(10)), (11, sample_page(11)), (12, sample_page(12))];
+
+        swap.swap_out_batch(&pages).unwrap();
+        for (page_id, _) in &pages {
+            assert!(swap.is_swapped_out(*page_id));
+        }
+
+        for (page_id, _) in &pages {
+            let read_back = swap.swap_in(*page_id).unwrap();
+            assert_eq!(read_back.header().page_id, *page_id);
+        }
+    }
+
+    #[test]
+    fn test_clear_truncates_and_forgets_everything() {
+        let mut swap = SwapFile::new(open_temp_file());
+        swap.swap_out(1, &sample_page(1)).unwrap();
+        swap.swap_out(2, &sample_page(2)).unwrap();
+
+        swap.clear().unwrap();
+
+        assert!(!swap.is_swapped_out(1));
+        assert!(!swap.is_swapped_out(2));
+        assert_eq!(swap.file.metadata().unwrap().len(), 0);
+        assert!(swap.swap_in(1).is_err());
+    }
+}
@@ -0,0 +1,366 @@
+//! MVCC copy-on-write page versioning built on `PageHeader::lsn`
+//!
+//! This is a single-writer, multi-reader transaction layer over the page
+//! store: the writer never mutates a page in place. Instead it allocates a
+//! fresh copy, stamps it with the committing transaction's sequence number
+//! (stored in the page's `lsn` field), and atomically swaps a root pointer
+//! (the manager's committed LSN) at commit. Concurrent readers pin a
+//! snapshot LSN when they begin and always resolve page lookups to the
+//! newest version whose `lsn <= snapshot_lsn`, so they never observe a
+//! partially-committed write.
+//!
+//! Old page versions are kept in the in-memory version map until no
+//! registered reader could still observe them; [`TransactionManager::gc`]
+//! reports which ones became collectible so a free-page allocator can
+//! reclaim their storage.
+
+use crate::common::error::Error;
+use crate::storage::page::Page;
+use crate::storage::page_constants::PageId;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Log sequence number: both the transaction sequence counter and the
+/// version stamp recorded in a page's `lsn` field.
+pub type Lsn = u64;
+
+/// Every version of every page ever committed, keyed by `PageId` and then
+/// ordered by the `Lsn` that produced it.
+#[derive(Default)]
+struct VersionMap {
+    versions: HashMap<PageId, BTreeMap<Lsn, Arc<Page>>>,
+}
+
+impl VersionMap {
+    fn insert(&mut self, page_id: PageId, lsn: Lsn, page: Arc<Page>) {
+        self.versions.entry(page_id).or_default().insert(lsn, page);
+    }
+
+    /// The newest version of `page_id` whose `lsn <= snapshot_lsn`.
+    fn resolve(&self, page_id: PageId, snapshot_lsn: Lsn) -> Option<Arc<Page>> {
+        self.versions
+            .get(&page_id)
+            .and_then(|versions| versions.range(..=snapshot_lsn).next_back())
+            .map(|(_, page)| Arc::clone(page))
+    }
+
+    /// Drop every version of every page strictly older than the newest
+    /// version still visible at or below `min_visible_lsn`, returning the
+    /// `(page_id, lsn)` pairs that were reclaimed.
+    fn collect_garbage(&mut self, min_visible_lsn: Lsn) -> Vec<(PageId, Lsn)> {
+        let mut reclaimed = Vec::new();
+        for (&page_id, versions) in &mut self.versions {
+            let keep_from = versions.range(..=min_visible_lsn).next_back().map(|(&lsn, _)| lsn);
+            let Some(keep_from) = keep_from else {
+                continue;
+            };
+            let stale: Vec<Lsn> = versions.keys().copied().filter(|&lsn| lsn < keep_from).collect();
+            for lsn in stale {
+                versions.remove(&lsn);
+                reclaimed.push((page_id, lsn));
+            }
+        }
+        reclaimed
+    }
+}
+
+/// Tracks currently pinned reader snapshots.
+#[derive(Default)]
+struct ReaderRegistry {
+    next_reader_id: u64,
+    active: BTreeMap<u64, Lsn>,
+}
+
+impl ReaderRegistry {
+    fn register(&mut self, snapshot_lsn: Lsn) -> u64 {
+        let id = self.next_reader_id;
+        self.next_reader_id += 1;
+        self.active.insert(id, snapshot_lsn);
+        id
+    }
+
+    fn unregister(&mut self, id: u64) {
+        self.active.remove(&id);
+    }
+
+    /// The oldest snapshot any live reader might still resolve against, or
+    /// `fallback` (typically the committed LSN) if there are none.
+    fn min_active_lsn(&self, fallback: Lsn) -> Lsn {
+        self.active.values().copied().min().unwrap_or(fallback)
+    }
+}
+
+/// Coordinates the single writer, concurrent readers, and page version
+/// history for one database.
+#[derive(Default)]
+pub struct TransactionManager {
+    version_map: Mutex<VersionMap>,
+    readers: Mutex<ReaderRegistry>,
+    committed_lsn: AtomicU64,
+    writer_lock: Mutex<()>,
+}
+
+impl TransactionManager {
+    /// Create a fresh transaction manager with no committed history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The LSN of the most recently committed transaction (the current
+    /// root pointer).
+    pub fn committed_lsn(&self) -> Lsn {
+        self.committed_lsn.load(Ordering::SeqCst)
+    }
+
+    /// Begin the single write transaction. Blocks until any other writer
+    /// has committed or aborted, enforcing one writer at a time.
+    pub fn begin_write(&self) -> WriteTxn<'_> {
+        // SAFETY-equivalent invariant: holding this guard for the lifetime
+        // of the `WriteTxn` is what makes the writer exclusive.
+        let guard = self
+            .writer_lock
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let lsn = self.committed_lsn() + 1;
+        WriteTxn {
+            manager: self,
+            _guard: guard,
+            lsn,
+            writes: Vec::new(),
+        }
+    }
+
+    /// Pin the current committed LSN as a read snapshot.
+    pub fn begin_read(&self) -> ReadTxn<'_> {
+        let snapshot_lsn = self.committed_lsn();
+        let reader_id = self
+            .readers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .register(snapshot_lsn);
+        ReadTxn {
+            manager: self,
+            reader_id,
+            snapshot_lsn,
+        }
+    }
+
+    /// Reclaim page versions that no live reader could still observe.
+    ///
+    /// Returns the `(page_id, lsn)` pairs that were dropped from the
+    /// in-memory version history; a free-page allocator can use this to
+    /// return their backing storage to the free list.
+    pub fn gc(&self) -> Vec<(PageId, Lsn)> {
+        let min_visible = self
+            .readers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .min_active_lsn(self.committed_lsn());
+        self.version_map
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .collect_garbage(min_visible)
+    }
+}
+
+/// The single active write transaction.
+///
+/// Writes are staged locally and only become visible to readers when
+/// [`WriteTxn::commit`] publishes them and bumps the root LSN.
+pub struct WriteTxn<'a> {
+    manager: &'a TransactionManager,
+    _guard: std::sync::MutexGuard<'a, ()>,
+    lsn: Lsn,
+    writes: Vec<(PageId, Page)>,
+}
+
+impl<'a> WriteTxn<'a> {
+    /// The LSN this transaction will commit with.
+    pub fn lsn(&self) -> Lsn {
+        self.lsn
+    }
+
+    /// Stage a copy-on-write update to `page_id`. The page is never
+    /// mutated in place; `page` should be a fresh copy the caller built
+    /// from the version it read. Its `lsn` is stamped with this
+    /// transaction's sequence number.
+    pub fn write_page(&mut self, page_id: PageId, mut page: Page) {
+        page.header_mut().lsn = self.lsn;
+        self.writes.push((page_id, page));
+    }
+
+    /// Read the version of `page_id` visible to this transaction,
+    /// including any of its own not-yet-committed writes.
+    pub fn read_page(&self, page_id: PageId) -> Option<Arc<Page>> {
+        if let Some((_, page)) = self.writes.iter().rev().find(|(id, _)| *id == page_id) {
+            // Not committed yet, so hand back a private copy.
+            return Some(Arc::new(clone_page(page)));
+        }
+        self.manager
+            .version_map
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .resolve(page_id, self.lsn - 1)
+    }
+
+    /// Atomically publish every staged write and advance the root pointer
+    /// to this transaction's LSN, making the writes visible to any reader
+    /// that begins afterward.
+    pub fn commit(self) -> Lsn {
+        {
+            let mut version_map = self
+                .manager
+                .version_map
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            for (page_id, page) in self.writes {
+                version_map.insert(page_id, self.lsn, Arc::new(page));
+            }
+        }
+        self.manager.committed_lsn.store(self.lsn, Ordering::SeqCst);
+        self.lsn
+    }
+
+    /// Discard every staged write without publishing them.
+    pub fn abort(self) {}
+}
+
+/// A pinned read snapshot. Every lookup resolves to the newest version at
+/// or below the snapshot LSN, regardless of writes committed afterward.
+pub struct ReadTxn<'a> {
+    manager: &'a TransactionManager,
+    reader_id: u64,
+    snapshot_lsn: Lsn,
+}
+
+impl<'a> ReadTxn<'a> {
+    /// The LSN this transaction's view is pinned to.
+    pub fn snapshot_lsn(&self) -> Lsn {
+        self.snapshot_lsn
+    }
+
+    /// Resolve `page_id` to the newest version visible in this snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotFound` if no version of `page_id` has ever been
+    /// committed at or below the snapshot LSN.
+    pub fn read_page(&self, page_id: PageId) -> Result<Arc<Page>, Error> {
+        self.manager
+            .version_map
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .resolve(page_id, self.snapshot_lsn)
+            .ok_or_else(|| Error::not_found(format!("no version of page {page_id} is visible")))
+    }
+}
+
+impl<'a> Drop for ReadTxn<'a> {
+    fn drop(&mut self) {
+        self.manager
+            .readers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .unregister(self.reader_id);
+    }
+}
+
+fn clone_page(page: &Page) -> Page {
+    let mut copy = Page::new();
+    copy.raw_mut().copy_from_slice(page.raw());
+    copy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::page_type::PageType;
+
+    #[test]
+    fn test_commit_advances_root_and_publishes_version() {
+        let manager = TransactionManager::new();
+        assert_eq!(manager.committed_lsn(), 0);
+
+        let mut txn = manager.begin_write();
+        let mut page = Page::new();
+        page.header_mut().page_type = PageType::Data;
+        page.header_mut().page_id = 7;
+        txn.write_page(7, page);
+        let lsn = txn.commit();
+
+        assert_eq!(lsn, 1);
+        assert_eq!(manager.committed_lsn(), 1);
+
+        let read = manager.begin_read();
+        let page = read.read_page(7).unwrap();
+        assert_eq!(page.header().page_id, 7);
+        assert_eq!(page.header().lsn, 1);
+    }
+
+    #[test]
+    fn test_reader_does_not_see_writes_after_its_snapshot() {
+        let manager = TransactionManager::new();
+
+        let mut txn = manager.begin_write();
+        txn.write_page(1, Page::new());
+        txn.commit();
+
+        let read = manager.begin_read();
+        assert_eq!(read.snapshot_lsn(), 1);
+
+        let mut txn2 = manager.begin_write();
+        let mut newer = Page::new();
+        newer.header_mut().flags = 0xAB;
+        txn2.write_page(1, newer);
+        txn2.commit();
+
+        // The earlier snapshot still resolves to the version at lsn 1.
+        let page = read.read_page(1).unwrap();
+        assert_eq!(page.header().lsn, 1);
+        assert_eq!(page.header().flags, 0);
+
+        // A fresh reader sees the newest committed version.
+        let fresh = manager.begin_read();
+        let page = fresh.read_page(1).unwrap();
+        assert_eq!(page.header().lsn, 2);
+        assert_eq!(page.header().flags, 0xAB);
+    }
+
+    #[test]
+    fn test_abort_does_not_publish_writes() {
+        let manager = TransactionManager::new();
+
+        let mut txn = manager.begin_write();
+        txn.write_page(5, Page::new());
+        txn.abort();
+
+        assert_eq!(manager.committed_lsn(), 0);
+        let read = manager.begin_read();
+        assert!(read.read_page(5).is_err());
+    }
+
+    #[test]
+    fn test_gc_reclaims_only_versions_below_every_reader() {
+        let manager = TransactionManager::new();
+
+        let mut txn = manager.begin_write();
+        txn.write_page(1, Page::new());
+        txn.commit();
+
+        let old_reader = manager.begin_read();
+
+        let mut txn2 = manager.begin_write();
+        txn2.write_page(1, Page::new());
+        txn2.commit();
+
+        // The old reader is still pinned to lsn 1, so its version must survive.
+        assert!(manager.gc().is_empty());
+
+        drop(old_reader);
+
+        // Now nothing needs the lsn-1 version anymore.
+        let reclaimed = manager.gc();
+        assert_eq!(reclaimed, vec![(1, 1)]);
+    }
+}
@@ -0,0 +1,6 @@
+//! Shared data types (not yet implemented)
+//!
+//! `src/lib.rs` has declared this module since before the storage-layer
+//! work in this series began, but nothing under it exists yet. This stub
+//! exists only so the crate has a module here to build, rather than
+//! failing to compile on a `mod` declaration with no backing file.
@@ -1,8 +1,8 @@
 //! Comprehensive edge case tests for Phase 2A page system
 
-use lumen::storage::checksum::*;
 use lumen::storage::page::Page;
 use lumen::storage::page_constants::*;
+use lumen::storage::page_header::PageHeader;
 use lumen::storage::page_io::*;
 use lumen::storage::page_type::PageType;
 use std::fs::File;
@@ -177,7 +177,7 @@ fn test_max_values() {
     page.header_mut().flags = u8::MAX;
     page.header_mut().free_space = u16::MAX;
     page.header_mut().checksum = u32::MAX;
-    page.header_mut().lsn = u32::MAX;
+    page.header_mut().lsn = u64::MAX;
 
     // Fill data area with max values
     for byte in page.data_mut() {
@@ -271,7 +271,7 @@ fn test_zero_page_special_case() {
     }
 
     // Even a zero page should have a non-zero checksum
-    let checksum = calculate_page_checksum(zero_page.raw()).unwrap();
+    let checksum = PageHeader::compute_checksum(zero_page.raw());
     assert_ne!(checksum, 0);
 
     // Setting checksum and verifying should work
@@ -316,7 +316,6 @@ fn test_page_type_transitions() {
 }
 
 #[test]
-#[ignore = "Temporarily disabled due to SIGBUS error"]
 fn test_mmap_edge_cases() -> Result<(), Box<dyn std::error::Error>> {
     let temp_file = NamedTempFile::new()?;
 
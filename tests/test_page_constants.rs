@@ -3,20 +3,25 @@
 use lumen::storage::page_constants::*;
 
 #[test]
+#[cfg(not(any(
+    feature = "page-addr64",
+    feature = "page-size-8k",
+    feature = "page-size-16k",
+    feature = "page-size-64k"
+)))]
 fn test_page_constants() {
     assert_eq!(PAGE_SIZE, 4096);
-    assert_eq!(PAGE_HEADER_SIZE, 16);
-    assert_eq!(PAGE_USABLE_SIZE, 4080);
+    assert_eq!(PAGE_HEADER_SIZE, 20);
+    assert_eq!(PAGE_USABLE_SIZE, 4076);
 }
 
 #[test]
 fn test_page_id_constants() {
     assert_eq!(INVALID_PAGE_ID, 0);
-    assert_eq!(MAX_PAGE_ID, u32::MAX);
+    assert_eq!(MAX_PAGE_ID, PageId::MAX);
 }
 
 #[test]
 fn test_page_size_is_power_of_two() {
     assert!(PAGE_SIZE.is_power_of_two());
-    assert_eq!(PAGE_SIZE, 4096);
 }
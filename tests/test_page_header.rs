@@ -7,12 +7,13 @@ use lumen::storage::page_type::PageType;
 
 #[test]
 fn test_page_header_size() {
-    // Must be exactly 16 bytes as per plan/storage-format.md
-    assert_eq!(std::mem::size_of::<PageHeader>(), 16);
+    // Must match PAGE_HEADER_SIZE as per plan/storage-format.md
+    assert_eq!(std::mem::size_of::<PageHeader>(), PAGE_HEADER_SIZE);
     assert_eq!(std::mem::align_of::<PageHeader>(), 1); // packed struct
 }
 
 #[test]
+#[cfg(not(feature = "page-addr64"))]
 fn test_page_header_field_offsets() {
     use std::mem::offset_of;
 
@@ -25,9 +26,23 @@ fn test_page_header_field_offsets() {
     assert_eq!(offset_of!(PageHeader, lsn), 12);
 }
 
+#[test]
+#[cfg(feature = "page-addr64")]
+fn test_page_header_field_offsets_addr64() {
+    use std::mem::offset_of;
+
+    // page_id widens to u64, shifting every later field.
+    assert_eq!(offset_of!(PageHeader, page_id), 0);
+    assert_eq!(offset_of!(PageHeader, page_type), 8);
+    assert_eq!(offset_of!(PageHeader, flags), 9);
+    assert_eq!(offset_of!(PageHeader, free_space), 10);
+    assert_eq!(offset_of!(PageHeader, checksum), 12);
+    assert_eq!(offset_of!(PageHeader, lsn), 16);
+}
+
 #[test]
 fn test_page_header_zero_copy() {
-    let mut buffer = [0u8; 16];
+    let mut buffer = [0u8; PAGE_HEADER_SIZE];
     let header = PageHeader {
         page_type: PageType::BTreeLeaf,
         flags: 0x42,
@@ -187,7 +187,7 @@ fn test_page_io_corruption_detection() -> Result<(), Box<dyn std::error::Error>>
     // With automatic checksum verification, read should fail
     assert!(result.is_err());
     if let Err(e) = result {
-        assert!(e.is_corruption());
+        assert!(e.is_checksum_mismatch());
     }
 
     Ok(())